@@ -0,0 +1,8 @@
+export default function () {
+  return {
+    dir: {
+      input: "src",
+      output: "dist",
+    },
+  };
+}
@@ -0,0 +1,3 @@
+import { greeting } from '@node-lerna/utils';
+
+console.log(greeting);
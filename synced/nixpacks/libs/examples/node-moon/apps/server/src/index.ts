@@ -0,0 +1,3 @@
+import { greeting } from 'utils';
+
+console.log(greeting);
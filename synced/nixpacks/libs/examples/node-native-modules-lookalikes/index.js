@@ -0,0 +1,3 @@
+const bcrypt = require("bcryptjs");
+
+console.log(bcrypt.hashSync("hello", 10));
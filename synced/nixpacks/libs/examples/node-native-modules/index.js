@@ -0,0 +1,15 @@
+const sharp = require("sharp");
+const { createCanvas } = require("canvas");
+const Client = require("pg-native");
+
+const canvas = createCanvas(200, 200);
+const ctx = canvas.getContext("2d");
+ctx.fillStyle = "#ff0000";
+ctx.fillRect(0, 0, 200, 200);
+
+sharp(canvas.toBuffer("image/png"))
+  .resize(100)
+  .toBuffer()
+  .then((image) => console.log(`Resized image to ${image.length} bytes`));
+
+console.log(`pg-native loaded: ${typeof Client}`);
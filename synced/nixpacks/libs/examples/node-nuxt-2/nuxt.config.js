@@ -0,0 +1,5 @@
+export default {
+  head: {
+    title: 'node-nuxt-2',
+  },
+}
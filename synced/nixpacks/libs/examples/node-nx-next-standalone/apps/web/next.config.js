@@ -0,0 +1,13 @@
+//@ts-check
+
+const { composePlugins, withNx } = require('@nx/next');
+
+/** @type {import('@nx/next/plugins/with-nx').WithNxOptions} **/
+const nextConfig = {
+  output: 'standalone',
+  nx: {
+    svgr: false,
+  },
+};
+
+module.exports = composePlugins(withNx)(nextConfig);
@@ -0,0 +1,12 @@
+import express from 'express';
+
+const app = express();
+const port = process.env.PORT || 3000;
+
+app.get('/', (_req, res) => {
+  res.send({ message: 'Hello from the api' });
+});
+
+app.listen(port, () => {
+  console.log(`Listening at http://localhost:${port}`);
+});
@@ -0,0 +1,3 @@
+import { greeting } from '@node-nx-workspace-layout/utils';
+
+console.log(greeting('api'));
@@ -0,0 +1,5 @@
+const http = require("http");
+
+http
+  .createServer((req, res) => res.end("Hello from node-playwright-tests"))
+  .listen(process.env.PORT || 3000);
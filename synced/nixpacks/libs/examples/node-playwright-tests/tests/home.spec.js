@@ -0,0 +1,5 @@
+const { test } = require("playwright/test");
+
+test("home page loads", async ({ page }) => {
+  await page.goto("http://localhost:3000");
+});
@@ -0,0 +1,9 @@
+const { chromium } = require("playwright");
+
+(async () => {
+  const browser = await chromium.launch();
+  const page = await browser.newPage();
+  await page.setContent("<h1>Hello from Playwright</h1>");
+  console.log(await page.textContent("h1"));
+  await browser.close();
+})();
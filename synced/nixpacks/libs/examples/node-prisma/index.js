@@ -0,0 +1,10 @@
+const { PrismaClient } = require('@prisma/client');
+
+const prisma = new PrismaClient();
+
+async function main() {
+  const count = await prisma.user.count();
+  console.log(`There are ${count} users`);
+}
+
+main().finally(() => prisma.$disconnect());
@@ -0,0 +1,3 @@
+import { greeting } from '@node-rush/utils';
+
+console.log(greeting);
@@ -0,0 +1,14 @@
+import adapter from '@sveltejs/adapter-static';
+
+/** @type {import('@sveltejs/kit').Config} */
+const config = {
+	kit: {
+		adapter: adapter({
+			pages: 'public',
+			assets: 'public',
+			fallback: 'index.html'
+		})
+	}
+};
+
+export default config;
@@ -0,0 +1 @@
+module.exports = { greeting: 'Hello from ui' };
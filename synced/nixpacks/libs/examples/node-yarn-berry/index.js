@@ -0,0 +1,3 @@
+const ms = require("ms");
+
+console.log(`Hello from Yarn Berry, one day is ${ms("1d")}ms`);
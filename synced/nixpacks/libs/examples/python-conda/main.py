@@ -0,0 +1,5 @@
+import numpy as np
+import requests
+
+print(f"Hello from conda, numpy {np.__version__}, requests {requests.__version__}")
+print(np.arange(5).sum())
@@ -0,0 +1,8 @@
+from fastapi import FastAPI
+
+app: FastAPI = FastAPI(title="api")
+
+
+@app.get("/")
+def read_root():
+    return {"message": "Hello from FastAPI"}
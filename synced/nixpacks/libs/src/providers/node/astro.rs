@@ -0,0 +1,119 @@
+// Code relating to Astro sites
+
+use regex::Regex;
+
+use crate::nixpacks::{app::App, environment::EnvironmentVariables};
+
+use super::PackageJson;
+
+const ASTRO_CONFIG_FILES: &[&str] = &[
+    "astro.config.mjs",
+    "astro.config.js",
+    "astro.config.ts",
+    "astro.config.mts",
+];
+const DEFAULT_OUTPUT_DIR: &str = "dist";
+
+pub struct Astro;
+
+impl Astro {
+    pub fn is_astro(app: &App) -> bool {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        package_json.has_dependency("astro")
+            && ASTRO_CONFIG_FILES
+                .iter()
+                .any(|config_file| app.includes_file(config_file))
+    }
+
+    fn read_config(app: &App) -> String {
+        ASTRO_CONFIG_FILES
+            .iter()
+            .find_map(|config_file| app.read_file(config_file).ok())
+            .unwrap_or_default()
+    }
+
+    fn get_output_dir(config: &str) -> String {
+        let r = Regex::new(r#"outDir:\s*['"`](.*?)['"`]"#).unwrap();
+        r.captures(config)
+            .and_then(|c| c.get(1))
+            .map_or(DEFAULT_OUTPUT_DIR.to_string(), |dir| {
+                dir.as_str()
+                    .trim_start_matches("./")
+                    .trim_end_matches('/')
+                    .to_string()
+            })
+    }
+
+    /// `@astrojs/node` in standalone mode builds a server that can be started directly.
+    /// In middleware mode the app mounts the handler in its own server, which is started
+    /// with the start script.
+    fn is_node_standalone(config: &str) -> bool {
+        let mode = Regex::new(r#"mode:\s*['"`]standalone['"`]"#).unwrap();
+        config.contains("@astrojs/node") && mode.is_match(config)
+    }
+
+    pub fn get_start_cmd(app: &App) -> Option<String> {
+        if !Astro::is_astro(app) {
+            return None;
+        }
+
+        let config = Astro::read_config(app);
+        if !Astro::is_node_standalone(&config) {
+            return None;
+        }
+
+        Some(format!(
+            "node ./{}/server/entry.mjs",
+            Astro::get_output_dir(&config)
+        ))
+    }
+
+    /// The standalone server only listens on localhost unless told otherwise
+    pub fn get_environment_variables(app: &App) -> EnvironmentVariables {
+        if Astro::get_start_cmd(app).is_some() {
+            EnvironmentVariables::from([("HOST".to_string(), "0.0.0.0".to_string())])
+        } else {
+            EnvironmentVariables::new()
+        }
+    }
+
+    /// Sites without an adapter are fully static, and are served like a SPA
+    pub fn get_static_output_dir(app: &App) -> Option<String> {
+        if !Astro::is_astro(app) {
+            return None;
+        }
+
+        let config = Astro::read_config(app);
+        if config.contains("adapter:") {
+            return None;
+        }
+
+        Some(Astro::get_output_dir(&config))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_astro_node_standalone() {
+        let app = App::new("examples/node-astro-ssr").unwrap();
+        assert_eq!(
+            Astro::get_start_cmd(&app),
+            Some("node ./dist/server/entry.mjs".to_string())
+        );
+        assert_eq!(Astro::get_static_output_dir(&app), None);
+        assert_eq!(
+            Astro::get_environment_variables(&app).get("HOST"),
+            Some(&"0.0.0.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_astro_static() {
+        let app = App::new("examples/node-astro-static").unwrap();
+        assert_eq!(Astro::get_start_cmd(&app), None);
+        assert_eq!(Astro::get_static_output_dir(&app), Some("dist".to_string()));
+    }
+}
@@ -0,0 +1,149 @@
+// Code relating to apps that drive a headless browser with Playwright or Puppeteer
+
+use crate::nixpacks::{
+    app::App,
+    environment::{Environment, EnvironmentVariables},
+};
+
+use super::{NodeProvider, PackageJson};
+
+const PLAYWRIGHT_BROWSERS_PATH: &str = "/app/.playwright";
+const HEADLESS_BROWSER_ENV_VAR: &str = "HEADLESS_BROWSER";
+
+// The shared libraries and fonts Chromium needs to launch
+// https://gist.github.com/winuxue/cfef08e2f5fe9dfc16a1d67a4ad38a01
+const CHROMIUM_APT_PKGS: &[&str] = &[
+    "libnss3",
+    "libatk1.0-0",
+    "libatk-bridge2.0-0",
+    "libcups2",
+    "libdrm2",
+    "libgbm1",
+    "libasound2t64",
+    "libpangocairo-1.0-0",
+    "libxcomposite1",
+    "libxdamage1",
+    "libxkbcommon0",
+    "libxrandr2",
+    "libxss1",
+    "libgtk-3-0",
+    "libxshmfence1",
+    "libglu1",
+    "fonts-liberation",
+    "fonts-noto-color-emoji",
+];
+
+pub struct HeadlessBrowser;
+
+impl HeadlessBrowser {
+    /// Browsers are only set up when the app needs them at runtime. Apps that only use them in
+    /// devDependencies, e.g. for tests, can opt in with `HEADLESS_BROWSER`.
+    fn uses_browser_dependency(app: &App, env: &Environment, dependency: &str) -> bool {
+        if env.is_config_variable_truthy(HEADLESS_BROWSER_ENV_VAR) {
+            return NodeProvider::uses_node_dependency(app, dependency);
+        }
+
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        package_json
+            .dependencies
+            .is_some_and(|deps| deps.contains_key(dependency))
+    }
+
+    fn uses_playwright(app: &App, env: &Environment) -> bool {
+        HeadlessBrowser::uses_browser_dependency(app, env, "playwright")
+    }
+
+    fn uses_puppeteer(app: &App, env: &Environment) -> bool {
+        HeadlessBrowser::uses_browser_dependency(app, env, "puppeteer")
+    }
+
+    pub fn get_apt_pkgs(app: &App, env: &Environment) -> Vec<String> {
+        let uses_chromium =
+            HeadlessBrowser::uses_browser_dependency(app, env, "@sparticuz/chromium");
+        if !(HeadlessBrowser::uses_playwright(app, env)
+            || HeadlessBrowser::uses_puppeteer(app, env)
+            || uses_chromium)
+        {
+            return Vec::new();
+        }
+
+        let mut pkgs: Vec<String> = CHROMIUM_APT_PKGS.iter().map(ToString::to_string).collect();
+        // Playwright and @sparticuz/chromium bring their own browser build
+        if HeadlessBrowser::uses_puppeteer(app, env) {
+            pkgs.push("chromium".to_string());
+        }
+
+        pkgs
+    }
+
+    /// Playwright doesn't download browsers when it is installed, so Chromium is fetched after the install
+    pub fn get_install_cmd(app: &App, env: &Environment) -> Option<String> {
+        HeadlessBrowser::uses_playwright(app, env).then(|| {
+            format!(
+                "{} playwright install chromium",
+                NodeProvider::get_package_manager_exec_command(app)
+            )
+        })
+    }
+
+    /// Keeps the downloaded browsers in the app directory so they are in the final image
+    pub fn get_environment_variables(app: &App, env: &Environment) -> EnvironmentVariables {
+        if HeadlessBrowser::uses_playwright(app, env) {
+            EnvironmentVariables::from([(
+                "PLAYWRIGHT_BROWSERS_PATH".to_string(),
+                PLAYWRIGHT_BROWSERS_PATH.to_string(),
+            )])
+        } else {
+            EnvironmentVariables::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_playwright() {
+        let app = App::new("examples/node-playwright").unwrap();
+        let env = Environment::default();
+        let pkgs = HeadlessBrowser::get_apt_pkgs(&app, &env);
+        assert!(pkgs.contains(&"libnss3".to_string()));
+        assert!(!pkgs.contains(&"chromium".to_string()));
+        assert_eq!(
+            HeadlessBrowser::get_install_cmd(&app, &env),
+            Some("npx --no-install playwright install chromium".to_string())
+        );
+        assert_eq!(
+            HeadlessBrowser::get_environment_variables(&app, &env).get("PLAYWRIGHT_BROWSERS_PATH"),
+            Some(&"/app/.playwright".to_string())
+        );
+    }
+
+    #[test]
+    fn test_playwright_dev_dependency() {
+        let app = App::new("examples/node-playwright-tests").unwrap();
+        let env = Environment::default();
+        assert!(HeadlessBrowser::get_apt_pkgs(&app, &env).is_empty());
+        assert_eq!(HeadlessBrowser::get_install_cmd(&app, &env), None);
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_HEADLESS_BROWSER".to_string(),
+            "true".to_string(),
+        )]));
+        assert!(!HeadlessBrowser::get_apt_pkgs(&app, &env).is_empty());
+        assert_eq!(
+            HeadlessBrowser::get_install_cmd(&app, &env),
+            Some("pnpm exec playwright install chromium".to_string())
+        );
+    }
+
+    #[test]
+    fn test_no_browser() {
+        let app = App::new("examples/node-prisma").unwrap();
+        let env = Environment::default();
+        assert!(HeadlessBrowser::get_apt_pkgs(&app, &env).is_empty());
+        assert_eq!(HeadlessBrowser::get_install_cmd(&app, &env), None);
+    }
+}
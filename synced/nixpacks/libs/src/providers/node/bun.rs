@@ -0,0 +1,133 @@
+// Code relating to Bun workspaces and bunfig.toml
+
+use serde::{Deserialize, Serialize};
+
+use crate::nixpacks::{app::App, environment::Environment};
+
+use super::{NodeProvider, PackageJson, Workspaces};
+
+const BUN_WORKSPACE_ENV_VAR: &str = "BUN_WORKSPACE";
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct BunConfig {
+    pub install: Option<BunInstallConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct BunInstallConfig {
+    pub cache: Option<BunCacheConfig>,
+}
+
+/// `cache` is either a directory, a boolean, or a table with a `dir`
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum BunCacheConfig {
+    Dir(String),
+    Enabled(bool),
+    Config { dir: Option<String> },
+}
+
+pub struct Bun;
+
+impl Bun {
+    fn get_workspace_globs(app: &App) -> Vec<String> {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        match package_json.workspaces {
+            Some(Workspaces::Array(workspaces)) => workspaces,
+            _ => Vec::new(),
+        }
+    }
+
+    /// The package set with `BUN_WORKSPACE`, or the only workspace package with a start script.
+    /// Nx and Turborepo select the package themselves.
+    pub fn get_workspace(app: &App, env: &Environment) -> Option<String> {
+        if NodeProvider::get_package_manager(app) != "bun"
+            || app.includes_file("nx.json")
+            || app.includes_file("turbo.json")
+        {
+            return None;
+        }
+
+        let globs = Bun::get_workspace_globs(app);
+        if globs.is_empty() {
+            return None;
+        }
+
+        if let Some(workspace) = env.get_config_variable(BUN_WORKSPACE_ENV_VAR) {
+            return Some(workspace);
+        }
+
+        let mut workspaces =
+            NodeProvider::find_workspace_packages_with_script(app, &globs, "start").ok()?;
+        if workspaces.len() == 1 {
+            workspaces.pop()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_build_cmd(app: &App, env: &Environment) -> Option<String> {
+        let workspace = Bun::get_workspace(app, env)?;
+        let has_build_script = NodeProvider::find_workspace_packages_with_script(
+            app,
+            &Bun::get_workspace_globs(app),
+            "build",
+        )
+        .is_ok_and(|packages| packages.contains(&workspace));
+        if !has_build_script {
+            return None;
+        }
+
+        Some(NodeProvider::get_workspace_script_cmd(
+            app, &workspace, "build",
+        ))
+    }
+
+    pub fn get_start_cmd(app: &App, env: &Environment) -> Option<String> {
+        let workspace = Bun::get_workspace(app, env)?;
+
+        Some(NodeProvider::get_workspace_script_cmd(
+            app, &workspace, "start",
+        ))
+    }
+
+    /// The install cache directory from `install.cache` in bunfig.toml
+    pub fn get_cache_dir(app: &App) -> Option<String> {
+        let config: BunConfig = app.read_toml("bunfig.toml").ok()?;
+        let dir = match config.install?.cache? {
+            BunCacheConfig::Dir(dir) => dir,
+            BunCacheConfig::Config { dir } => dir?,
+            BunCacheConfig::Enabled(_) => return None,
+        };
+
+        Some(match dir.strip_prefix("~/") {
+            Some(dir) => format!("/root/{dir}"),
+            None => dir,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bun_workspaces() {
+        let app = App::new("examples/node-bun-workspaces").unwrap();
+        let env = Environment::default();
+
+        assert_eq!(Bun::get_workspace(&app, &env), Some("api".to_string()));
+        assert_eq!(
+            Bun::get_build_cmd(&app, &env),
+            Some("bun run --filter api build".to_string())
+        );
+        assert_eq!(
+            Bun::get_start_cmd(&app, &env),
+            Some("bun run --filter api start".to_string())
+        );
+        assert_eq!(
+            Bun::get_cache_dir(&app),
+            Some("/root/.cache/bun".to_string())
+        );
+    }
+}
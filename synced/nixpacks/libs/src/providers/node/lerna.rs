@@ -0,0 +1,114 @@
+// Code relating to Lerna monorepos
+
+use serde::{Deserialize, Serialize};
+
+use crate::nixpacks::{app::App, environment::Environment};
+
+use super::{turborepo::pnpm_workspaces, NodeProvider, PackageJson, Workspaces};
+
+const LERNA_APP_NAME_ENV_VAR: &str = "LERNA_APP_NAME";
+const DEFAULT_PACKAGES_GLOB: &str = "packages/*";
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct LernaJson {
+    pub packages: Option<Vec<String>>,
+}
+
+pub struct Lerna;
+
+impl Lerna {
+    pub fn is_lerna_repo(app: &App, env: &Environment) -> bool {
+        app.includes_file("lerna.json") && Lerna::get_app_name(app, env).is_some()
+    }
+
+    /// The package set with `LERNA_APP_NAME`, or the only package with a start script
+    pub fn get_app_name(app: &App, env: &Environment) -> Option<String> {
+        if let Some(app_name) = env.get_config_variable(LERNA_APP_NAME_ENV_VAR) {
+            return Some(app_name);
+        }
+
+        let mut app_names = NodeProvider::find_workspace_packages_with_script(
+            app,
+            &Lerna::get_package_globs(app),
+            "start",
+        )
+        .ok()?;
+        if app_names.len() == 1 {
+            app_names.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Lerna reads packages from lerna.json, falling back to the package manager's workspaces
+    fn get_package_globs(app: &App) -> Vec<String> {
+        let lerna_json: LernaJson = app.read_json("lerna.json").unwrap_or_default();
+        if let Some(packages) = lerna_json.packages {
+            return packages;
+        }
+
+        if NodeProvider::get_package_manager(app) == "pnpm" {
+            if let Ok(packages) = pnpm_workspaces(app) {
+                return packages;
+            }
+        }
+
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        if let Some(Workspaces::Array(workspaces)) = package_json.workspaces {
+            return workspaces;
+        }
+
+        vec![DEFAULT_PACKAGES_GLOB.to_string()]
+    }
+
+    pub fn get_build_cmd(app: &App, env: &Environment) -> Option<String> {
+        let app_name = Lerna::get_app_name(app, env)?;
+
+        // Build the local packages the app depends on as well
+        Some(format!(
+            "{} lerna run build --scope={app_name} --include-dependencies",
+            NodeProvider::get_package_manager_dlx_command(app)
+        ))
+    }
+
+    pub fn get_start_cmd(app: &App, env: &Environment) -> Option<String> {
+        let app_name = Lerna::get_app_name(app, env)?;
+
+        Some(NodeProvider::get_workspace_script_cmd(
+            app, &app_name, "start",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_lerna() {
+        let app = App::new("examples/node-lerna").unwrap();
+        let env = Environment::default();
+
+        assert!(Lerna::is_lerna_repo(&app, &env));
+        assert_eq!(
+            Lerna::get_build_cmd(&app, &env),
+            Some(
+                "npx lerna run build --scope=@node-lerna/server --include-dependencies".to_string()
+            )
+        );
+        assert_eq!(
+            Lerna::get_start_cmd(&app, &env),
+            Some("npm --workspace @node-lerna/server run start".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_LERNA_APP_NAME".to_string(),
+            "@node-lerna/utils".to_string(),
+        )]));
+        assert_eq!(
+            Lerna::get_app_name(&app, &env),
+            Some("@node-lerna/utils".to_string())
+        );
+    }
+}
@@ -1,8 +1,28 @@
-use self::{moon::Moon, nx::Nx, spa::SpaProvider, turborepo::Turborepo};
-use super::Provider;
+use self::{
+    astro::Astro,
+    browser::HeadlessBrowser,
+    bun::Bun,
+    lerna::Lerna,
+    moon::Moon,
+    native::NativeModules,
+    next::Next,
+    nuxt::Nuxt,
+    nx::Nx,
+    pnpm::PnpmWorkspace,
+    prisma::Prisma,
+    registry::NpmRegistry,
+    remix::Remix,
+    rush::Rush,
+    spa::{eleventy::EleventySpaProvider, expo::ExpoSpaProvider, SpaProvider},
+    sveltekit::SvelteKit,
+    turborepo::Turborepo,
+    yarn::YarnPnp,
+};
+use super::{Provider, ProviderMetadata};
 use crate::nixpacks::plan::merge::Mergeable;
 use crate::nixpacks::{
     app::App,
+    asdf::parse_tool_versions_content,
     environment::{Environment, EnvironmentVariables},
     nix::pkg::Pkg,
     plan::{
@@ -13,14 +33,29 @@ use crate::nixpacks::{
 use anyhow::Result;
 use node_semver::Range;
 use path_slash::PathExt;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 
+mod astro;
+mod browser;
+mod bun;
+mod lerna;
 mod moon;
+mod native;
+mod next;
+mod nuxt;
 mod nx;
-mod spa;
+mod pnpm;
+mod prisma;
+mod registry;
+mod remix;
+mod rush;
+pub(crate) mod spa;
+mod sveltekit;
 mod turborepo;
+mod yarn;
 
 // allows package manager versions to be updated without cutting a new nixpacks binary
 pub const NODE_OVERLAY: &str = "https://github.com/railwayapp/nix-npm-overlay/archive/main.tar.gz";
@@ -30,6 +65,27 @@ const BUN_NIXPKGS_ARCHIVE: &str = "31fb21469e34b6b5c7be77b9a35bae43d0c598e9";
 
 const DEFAULT_NODE_VERSION: u32 = 18;
 
+const NODE_PRUNE_ENV_VAR: &str = "NODE_PRUNE";
+const MEMORY_LIMIT_ENV_VAR: &str = "MEMORY_LIMIT_MB";
+const IGNORE_SCRIPTS_ENV_VAR: &str = "NODE_IGNORE_SCRIPTS";
+const TRUSTED_DEPENDENCIES_ENV_VAR: &str = "NODE_TRUSTED_DEPENDENCIES";
+const PNPM_FETCH_ENV_VAR: &str = "PNPM_FETCH";
+
+/// Start scripts that only run the framework's own server, which the detected framework start
+/// command replaces
+const FRAMEWORK_START_SCRIPTS: &[&str] = &[
+    "next start",
+    "expo start",
+    "react-scripts start",
+    "vite",
+    "astro",
+    "nuxt",
+    "nuxi",
+    "eleventy",
+];
+
+const MISE_CONFIG_FILES: &[&str] = &["mise.toml", ".mise.toml"];
+
 // From: https://lazamar.co.uk/nix-versions/?channel=nixpkgs-unstable&package=nodejs
 // Maps Node version to nixpkgs archive hash
 const AVAILABLE_NODE_VERSIONS: &[(u32, &str)] = &[
@@ -113,16 +169,10 @@ pub struct PackageJson {
 impl PackageJson {
     /// searches dependencies and dev_dependencies in package.json for a given dependency
     fn has_dependency(&self, dep: &str) -> bool {
-        if let Some(deps) = &self.dependencies {
-            if deps.contains_key(dep) {
-                return true;
-            }
-        } else if let Some(deps) = &self.dev_dependencies {
-            if deps.contains_key(dep) {
-                return true;
-            }
-        }
-        false
+        [&self.dependencies, &self.dev_dependencies]
+            .into_iter()
+            .flatten()
+            .any(|deps| deps.contains_key(dep))
     }
 }
 
@@ -135,60 +185,90 @@ impl Provider for NodeProvider {
     }
 
     fn detect(&self, app: &App, _env: &Environment) -> Result<bool> {
-        Ok(app.includes_file("package.json"))
+        Ok(app.includes_file("package.json") || app.includes_file("rush.json"))
+    }
+
+    fn metadata(&self, app: &App, env: &Environment) -> Result<ProviderMetadata> {
+        let nx = Nx::new(app);
+        let nx_metadata = nx
+            .as_ref()
+            .map(|nx| nx.get_nx_metadata(app, env))
+            .unwrap_or_default();
+        let mut metadata = vec![(is_nx_monorepo(nx.as_ref(), app, env), "nx")];
+        metadata.extend(nx_metadata.iter().map(|value| (true, value.as_str())));
+
+        Ok(ProviderMetadata::from(metadata))
     }
 
     fn get_build_plan(&self, app: &App, env: &Environment) -> Result<Option<BuildPlan>> {
+        let nx = Nx::new(app);
+        let nx = nx.as_ref();
+
         // Setup
         let mut setup = Phase::setup(Some(NodeProvider::get_nix_packages(app, env)?));
         setup.set_nix_archive(NodeProvider::get_nix_archive(app)?);
-        if NodeProvider::uses_node_dependency(app, "prisma") {
+        if NodeProvider::uses_node_dependency(app, "prisma") || Prisma::is_prisma(app) {
             setup.add_nix_pkgs(&[Pkg::new("openssl")]);
         }
 
-        if NodeProvider::uses_node_dependency(app, "sharp") {
-            setup.add_pkgs_libs(vec!["gcc-unwrapped".to_string()]);
+        if let Some(corepack_cmd) = NodeProvider::get_corepack_setup_cmd(app, env)? {
+            setup.add_cmd(corepack_cmd);
         }
 
-        if NodeProvider::uses_node_dependency(app, "puppeteer") {
-            // https://gist.github.com/winuxue/cfef08e2f5fe9dfc16a1d67a4ad38a01
-            setup.add_apt_pkgs(vec![
-                "libnss3".to_string(),
-                "libatk1.0-0".to_string(),
-                "libatk-bridge2.0-0".to_string(),
-                "libcups2".to_string(),
-                "libgbm1".to_string(),
-                "libasound2t64".to_string(),
-                "libpangocairo-1.0-0".to_string(),
-                "libxss1".to_string(),
-                "libgtk-3-0".to_string(),
-                "libxshmfence1".to_string(),
-                "libglu1".to_string(),
-                "chromium".to_string(),
-            ]);
-        }
+        setup.add_nix_pkgs(&NativeModules::get_nix_pkgs(app));
+        setup.add_pkgs_libs(NativeModules::get_nix_libs(app));
 
-        if NodeProvider::uses_node_dependency(app, "canvas") {
-            setup.add_pkgs_libs(vec!["libuuid".to_string(), "libGL".to_string()]);
+        let browser_pkgs = HeadlessBrowser::get_apt_pkgs(app, env);
+        if !browser_pkgs.is_empty() {
+            setup.add_apt_pkgs(browser_pkgs);
         }
 
         // Install
-        let corepack = NodeProvider::uses_corepack(app, env)?;
-        let mut install = Phase::install(if corepack {
-            Some("npm install -g corepack@0.24.1 && corepack enable".to_string())
+        let workspace_install_cmd = nx
+            .and_then(|nx| nx.get_nx_prune_install_cmd(app, env))
+            .or_else(|| Turborepo::get_prune_install_cmd(app, env))
+            .or_else(|| PnpmWorkspace::get_install_cmd(app, env))
+            .or_else(|| Rush::get_install_cmd(app, env));
+        let fetch = if workspace_install_cmd.is_none() {
+            NodeProvider::get_pnpm_fetch_phase(app, env)
         } else {
-            NodeProvider::get_install_command(app)
-        });
-
-        if corepack {
-            let install_cmd = NodeProvider::get_install_command(app);
-
-            if install_cmd.is_some() {
-                install.add_cmd(install_cmd.unwrap_or_default());
+            None
+        };
+        let install_cmd = if fetch.is_some() {
+            Some("pnpm i --offline --frozen-lockfile".to_string())
+        } else {
+            workspace_install_cmd.or_else(|| NodeProvider::get_install_command(app))
+        };
+        let install_cmd = if env.is_config_variable_truthy(IGNORE_SCRIPTS_ENV_VAR) {
+            install_cmd.map(|cmd| NodeProvider::get_ignore_scripts_install_cmd(app, env, cmd))
+        } else {
+            install_cmd
+        };
+        let mut install = Phase::install(None);
+        if let Some(install_cmd) = install_cmd {
+            // Installing offline after a fetch doesn't need registry auth
+            if fetch.is_some() {
+                install.add_cmd(install_cmd);
+            } else {
+                NpmRegistry::add_authenticated_cmd(&mut install, app, env, install_cmd);
             }
         }
+        if fetch.is_some() {
+            install.depends_on_phase("fetch");
+        }
+        for postinstall_cmd in NodeProvider::get_postinstall_cmds(app, env)? {
+            install.add_cmd(postinstall_cmd);
+        }
+        if let Some(generate_cmd) = Prisma::get_generate_cmd(app) {
+            install.add_cmd(generate_cmd);
+        }
+        if let Some(browser_cmd) = HeadlessBrowser::get_install_cmd(app, env) {
+            install.add_cmd(browser_cmd);
+        }
 
-        install.add_cache_directory(NodeProvider::get_package_manager_cache_dir(app));
+        if fetch.is_none() && !YarnPnp::is_zero_install(app) {
+            install.add_cache_directory(NodeProvider::get_package_manager_cache_dir(app));
+        }
         install.add_path("/app/node_modules/.bin".to_string());
 
         // Cypress cache directory
@@ -197,8 +277,21 @@ impl Provider for NodeProvider {
             install.add_cache_directory((*CYPRESS_CACHE_DIR).to_string());
         }
 
+        // Test
+        let test = nx
+            .and_then(|nx| nx.get_nx_test_cmd(app, env))
+            .map(|test_cmd| {
+                let mut test = Phase::new("test");
+                test.depends_on_phase("install");
+                test.add_cmd(test_cmd);
+                test
+            });
+
         // Build
-        let mut build = Phase::build(NodeProvider::get_build_cmd(app, env)?);
+        let mut build = Phase::build(NodeProvider::get_build_cmd(app, env, nx)?);
+        if test.is_some() {
+            build.depends_on_phase("test");
+        }
 
         // Next build cache directories
         let next_cache_dirs = NodeProvider::find_next_packages(app)?;
@@ -211,8 +304,10 @@ impl Provider for NodeProvider {
             });
         }
 
-        // Node modules cache directory
-        build.add_cache_directory((*NODE_MODULES_CACHE_DIR).to_string());
+        // Node modules cache directory, PnP installs don't have a node_modules folder
+        if !YarnPnp::is_pnp(app) {
+            build.add_cache_directory((*NODE_MODULES_CACHE_DIR).to_string());
+        }
         let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
         if let Some(cache_directories) = package_json.cache_directories {
             for dir in cache_directories {
@@ -223,28 +318,92 @@ impl Provider for NodeProvider {
         NodeProvider::cache_tsbuildinfo_file(app, &mut build);
 
         if Moon::is_moon_repo(app, env) {
-            build.add_cache_directory(".moon/cache/outputs");
+            build.add_cache_directory(".moon/cache");
+        }
+
+        // Older Nx versions cache in node_modules/.cache/nx, which is already covered above
+        if let Some(nx) = nx.filter(|nx| nx.is_nx_monorepo(app, env)) {
+            build.add_cache_directory(".nx/cache");
+            for cmd in nx.get_nx_post_build_cmds(app, env) {
+                build.add_cmd(cmd);
+            }
+        }
+
+        if NodeProvider::is_next_standalone(app, env, nx) {
+            let public_dir = app.includes_directory("public").then_some("public");
+            for cmd in Next::get_standalone_copy_cmds("", public_dir) {
+                build.add_cmd(cmd);
+            }
         }
 
         // Start
-        let start = NodeProvider::get_start_cmd(app, env)?.map(StartPhase::new);
+        let migrate_cmd = Prisma::get_migrate_cmd(app, env, nx)?;
+        let mut start =
+            NodeProvider::get_start_cmd(app, env, nx)?.map(|start_cmd| match &migrate_cmd {
+                Some(migrate_cmd) => StartPhase::new(format!("{migrate_cmd} && {start_cmd}")),
+                None => StartPhase::new(start_cmd),
+            });
 
-        let mut phases = vec![setup, install, build];
-        if let Some(caddy) = SpaProvider::caddy_phase(app, env) {
-            phases.push(caddy);
+        // The standalone server bundles the node_modules it needs, so the rest are left behind
+        if let Some(start) = start.as_mut() {
+            if NodeProvider::uses_next_standalone_start(app, env, nx) {
+                let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+                let node_pkg = NodeProvider::get_nix_node_pkg(&package_json, app, env)?;
+                let node_major = node_pkg.name.trim_start_matches("nodejs_");
+                start.run_in_image(format!("node:{node_major}-slim"));
+                start.add_file_dependency(".next/standalone");
+            }
         }
-        let is_spa = SpaProvider::is_spa(app);
+
+        // Prune
+        let prune = env.is_config_variable_truthy(NODE_PRUNE_ENV_VAR).then(|| {
+            let mut prune = Phase::new("prune");
+            prune.depends_on_phase("build");
+            NpmRegistry::add_authenticated_cmd(
+                &mut prune,
+                app,
+                env,
+                NodeProvider::get_prune_command(app),
+            );
+            prune.add_cache_directory(NodeProvider::get_package_manager_cache_dir(app));
+            prune
+        });
+
+        let mut phases = vec![setup];
+        phases.extend(fetch);
+        phases.push(install);
+        phases.extend(test);
+        phases.push(build);
+        phases.extend(prune);
+        let caddy = SpaProvider::caddy_phase(app, env, nx);
+        let uses_caddy = caddy.is_some();
+        phases.extend(caddy);
+        let is_spa = SpaProvider::is_spa(app, env, nx);
 
         let mut plan = BuildPlan::new(&phases, start);
-        if SpaProvider::caddy_phase(app, env).is_some() {
+        if uses_caddy {
             plan.add_static_assets(SpaProvider::static_assets());
         }
         plan.add_variables(NodeProvider::get_node_environment_variables());
+        plan.add_variables(Astro::get_environment_variables(app));
+        plan.add_variables(HeadlessBrowser::get_environment_variables(app, env));
+        plan.add_variables(YarnPnp::get_environment_variables(app));
+        if env.is_config_variable_truthy(IGNORE_SCRIPTS_ENV_VAR)
+            && NodeProvider::get_package_manager(app) == "yarn"
+            && app.includes_file(".yarnrc.yml")
+        {
+            // Yarn 2+ has no --ignore-scripts flag, and the setting only applies to installs
+            plan.add_variables(EnvironmentVariables::from([(
+                "YARN_ENABLE_SCRIPTS".to_string(),
+                "false".to_string(),
+            )]));
+        }
+        plan.add_variables(NodeProvider::get_memory_environment_variables(env)?);
         if is_spa {
             plan.add_variables(EnvironmentVariables::from([(
                 "NIXPACKS_SPA_OUTPUT_DIR".to_string(),
                 env.get_config_variable("SPA_OUT_DIR")
-                    .unwrap_or(SpaProvider::get_output_directory(app)),
+                    .unwrap_or(SpaProvider::get_output_directory(app, env, nx)),
             )]));
         }
         Ok(Some(plan))
@@ -261,6 +420,45 @@ impl NodeProvider {
         ])
     }
 
+    /// Sizes the V8 heap to the memory limit, leaving a quarter for the rest of the process
+    pub fn get_memory_environment_variables(env: &Environment) -> Result<EnvironmentVariables> {
+        let Some(limit) = env.get_config_variable(MEMORY_LIMIT_ENV_VAR) else {
+            return Ok(EnvironmentVariables::new());
+        };
+        let limit_mb = limit.trim().parse::<u32>().map_err(|_| {
+            anyhow::anyhow!(
+                "NIXPACKS_{MEMORY_LIMIT_ENV_VAR} must be a number of megabytes, got '{limit}'"
+            )
+        })?;
+
+        let heap_flag = format!("--max-old-space-size={}", limit_mb / 4 * 3);
+        let node_options = match env.get_variable("NODE_OPTIONS") {
+            Some(node_options) if !node_options.trim().is_empty() => {
+                format!("{} {heap_flag}", node_options.trim())
+            }
+            _ => heap_flag,
+        };
+
+        Ok(EnvironmentVariables::from([(
+            "NODE_OPTIONS".to_string(),
+            node_options,
+        )]))
+    }
+
+    /// A Next.js app at the root of the repo that builds with `output: "standalone"`
+    pub fn is_next_standalone(app: &App, env: &Environment, nx: Option<&Nx>) -> bool {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        package_json.has_dependency("next")
+            && !is_nx_monorepo(nx, app, env)
+            && Next::is_standalone(app, "")
+    }
+
+    /// Whether the app is started from `.next/standalone` in a slim Node image
+    pub fn uses_next_standalone_start(app: &App, env: &Environment, nx: Option<&Nx>) -> bool {
+        NodeProvider::is_next_standalone(app, env, nx)
+            && !NodeProvider::has_custom_start_script(app)
+    }
+
     pub fn has_script(app: &App, script: &str) -> Result<bool> {
         let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
         if let Some(scripts) = package_json.scripts {
@@ -272,6 +470,20 @@ impl NodeProvider {
         Ok(false)
     }
 
+    /// A start script that does more than run the framework's server, which always wins over the
+    /// framework start commands
+    pub fn has_custom_start_script(app: &App) -> bool {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        package_json
+            .scripts
+            .and_then(|scripts| scripts.get("start").cloned())
+            .is_some_and(|start| {
+                !FRAMEWORK_START_SCRIPTS.iter().any(|framework_start| {
+                    start == *framework_start || start.starts_with(&format!("{framework_start} "))
+                })
+            })
+    }
+
     pub fn uses_corepack(app: &App, env: &Environment) -> Result<bool> {
         let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
         let node_pkg = NodeProvider::get_nix_node_pkg(&package_json, app, env)?;
@@ -293,13 +505,27 @@ impl NodeProvider {
         Ok(false)
     }
 
-    pub fn get_build_cmd(app: &App, env: &Environment) -> Result<Option<String>> {
+    /// Activates the exact package manager version from the `packageManager` field
+    pub fn get_corepack_setup_cmd(app: &App, env: &Environment) -> Result<Option<String>> {
+        if !NodeProvider::uses_corepack(app, env)? {
+            return Ok(None);
+        }
+
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        Ok(package_json.package_manager.map(|package_manager| {
+            format!(
+                "npm install -g corepack@0.24.1 && corepack enable && corepack prepare {package_manager} --activate"
+            )
+        }))
+    }
+
+    pub fn get_build_cmd(app: &App, env: &Environment, nx: Option<&Nx>) -> Result<Option<String>> {
         if Moon::is_moon_repo(app, env) {
             return Ok(Some(Moon::get_build_cmd(app, env)));
         }
 
-        if Nx::is_nx_monorepo(app, env) {
-            if let Some(nx_build_cmd) = Nx::get_nx_build_cmd(app, env) {
+        if let Some(nx) = nx.filter(|nx| nx.is_nx_monorepo(app, env)) {
+            if let Some(nx_build_cmd) = nx.get_nx_build_cmd(app, env) {
                 return Ok(Some(nx_build_cmd));
             }
         }
@@ -310,15 +536,37 @@ impl NodeProvider {
             }
         }
 
+        if Lerna::is_lerna_repo(app, env) {
+            if let Some(lerna_build_cmd) = Lerna::get_build_cmd(app, env) {
+                return Ok(Some(lerna_build_cmd));
+            }
+        }
+
+        if let Some(pnpm_build_cmd) = PnpmWorkspace::get_build_cmd(app, env) {
+            return Ok(Some(pnpm_build_cmd));
+        }
+
+        if let Some(bun_build_cmd) = Bun::get_build_cmd(app, env) {
+            return Ok(Some(bun_build_cmd));
+        }
+
+        if Rush::is_rush_repo(app, env) {
+            if let Some(rush_build_cmd) = Rush::get_build_cmd(app, env) {
+                return Ok(Some(rush_build_cmd));
+            }
+        }
+
         if NodeProvider::has_script(app, "build")? {
             let pkg_manager = NodeProvider::get_package_manager(app);
             Ok(Some(format!("{pkg_manager} run build")))
         } else {
-            Ok(None)
+            Ok(Remix::get_build_cmd(app)
+                .or_else(|| ExpoSpaProvider::get_build_cmd(app))
+                .or_else(|| EleventySpaProvider::get_build_cmd(app)))
         }
     }
 
-    pub fn get_start_cmd(app: &App, env: &Environment) -> Result<Option<String>> {
+    pub fn get_start_cmd(app: &App, env: &Environment, nx: Option<&Nx>) -> Result<Option<String>> {
         let executor = NodeProvider::get_executor(app);
         let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
 
@@ -326,8 +574,9 @@ impl NodeProvider {
             return Ok(Some(Moon::get_start_cmd(app, env)));
         }
 
-        if Nx::is_nx_monorepo(app, env) {
-            if let Some(nx_start_cmd) = Nx::get_nx_start_cmd(app, env)? {
+        if let Some(nx) = nx {
+            nx.validate_nx_app_name(app, env)?;
+            if let Some(nx_start_cmd) = nx.get_nx_start_cmd(app, env)? {
                 return Ok(Some(nx_start_cmd));
             }
         }
@@ -340,15 +589,59 @@ impl NodeProvider {
             }
         }
 
-        if let Some(start) = SpaProvider::start_command(app, env) {
-            return Ok(Some(start));
+        if Lerna::is_lerna_repo(app, env) {
+            if let Some(lerna_start_cmd) = Lerna::get_start_cmd(app, env) {
+                return Ok(Some(lerna_start_cmd));
+            }
+        }
+
+        if let Some(pnpm_start_cmd) = PnpmWorkspace::get_start_cmd(app, env) {
+            return Ok(Some(pnpm_start_cmd));
+        }
+
+        if let Some(bun_start_cmd) = Bun::get_start_cmd(app, env) {
+            return Ok(Some(bun_start_cmd));
+        }
+
+        if Rush::is_rush_repo(app, env) {
+            if let Some(rush_start_cmd) = Rush::get_start_cmd(app, env) {
+                return Ok(Some(rush_start_cmd));
+            }
         }
 
         let package_manager = NodeProvider::get_package_manager(app);
+        if NodeProvider::has_custom_start_script(app) {
+            return Ok(Some(format!("{package_manager} run start")));
+        }
+
+        if NodeProvider::is_next_standalone(app, env, nx) {
+            return Ok(Some(Next::get_standalone_start_cmd("")));
+        }
+
+        if let Some(sveltekit_start_cmd) = SvelteKit::get_start_cmd(app) {
+            return Ok(Some(sveltekit_start_cmd));
+        }
+
+        if let Some(astro_start_cmd) = Astro::get_start_cmd(app) {
+            return Ok(Some(astro_start_cmd));
+        }
+
+        if let Some(nuxt_start_cmd) = Nuxt::get_start_cmd(app, env) {
+            return Ok(Some(nuxt_start_cmd));
+        }
+
+        if let Some(start) = SpaProvider::start_command(app, env, nx) {
+            return Ok(Some(start));
+        }
+
         if NodeProvider::has_script(app, "start")? {
             return Ok(Some(format!("{package_manager} run start")));
         }
 
+        if let Some(remix_start_cmd) = Remix::get_start_cmd(app) {
+            return Ok(Some(remix_start_cmd));
+        }
+
         if let Some(main) = package_json.main {
             if app.includes_file(&main) {
                 return Ok(Some(format!("{executor} {main}")));
@@ -393,10 +686,38 @@ impl NodeProvider {
             None
         };
 
+        let tool_versions_node_version = if app.includes_file(".tool-versions") {
+            // asdf names the plugin nodejs, mise also accepts node
+            let tool_versions = parse_tool_versions_content(&app.read_file(".tool-versions")?);
+            tool_versions
+                .get("nodejs")
+                .or_else(|| tool_versions.get("node"))
+                .map(|version| parse_mise_version(version))
+        } else {
+            None
+        };
+
+        let mise_node_version = MISE_CONFIG_FILES
+            .iter()
+            .find(|file| app.includes_file(file))
+            .and_then(|file| app.read_toml::<toml::Value>(file).ok())
+            .and_then(|config| {
+                // The version is either a string, a list of versions or a table with a version
+                let node = config.get("tools")?.get("node")?;
+                let version = match node {
+                    toml::Value::Array(versions) => versions.first()?,
+                    toml::Value::Table(options) => options.get("version")?,
+                    version => version,
+                };
+                version.as_str().map(parse_mise_version)
+            });
+
         let node_version = env_node_version
             .or(pkg_node_version)
             .or(nvmrc_node_version)
-            .or(dot_node_version);
+            .or(dot_node_version)
+            .or(tool_versions_node_version)
+            .or(mise_node_version);
 
         let node_version = match node_version {
             Some(node_version) => node_version,
@@ -449,11 +770,73 @@ impl NodeProvider {
         match pkg_manager.as_str() {
             "pnpm" => "pnpx",
             "yarn" => "yarn",
+            "bun" => "bunx",
             _ => "npx",
         }
         .to_string()
     }
 
+    /// Runs a binary installed in `node_modules`, without downloading it when it is missing
+    pub fn get_package_manager_exec_command(app: &App) -> String {
+        let pkg_manager = NodeProvider::get_package_manager(app);
+        match pkg_manager.as_str() {
+            "pnpm" => "pnpm exec",
+            "yarn" => "yarn",
+            "bun" => "bunx --no-install",
+            _ => "npx --no-install",
+        }
+        .to_string()
+    }
+
+    /// Finds the workspace packages matching `globs` that define `script`, returning each package's name
+    pub fn find_workspace_packages_with_script(
+        app: &App,
+        globs: &[String],
+        script: &str,
+    ) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for glob in globs {
+            for dir in app.find_directories(glob)? {
+                let relative = app.strip_source_path(dir.as_path())?;
+                let package_json: PackageJson = match app
+                    .read_json(&format!("{}/package.json", relative.to_slash().unwrap()))
+                {
+                    Ok(package_json) => package_json,
+                    Err(_) => continue,
+                };
+
+                let has_script = package_json
+                    .scripts
+                    .as_ref()
+                    .is_some_and(|scripts| scripts.contains_key(script));
+                if let (true, Some(name)) = (
+                    has_script,
+                    package_json
+                        .name
+                        .or_else(|| dir.file_name()?.to_str().map(String::from)),
+                ) {
+                    names.push(name);
+                }
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Runs a script of a single workspace package with the package manager
+    pub fn get_workspace_script_cmd(app: &App, name: &str, script: &str) -> String {
+        let pkg_manager = NodeProvider::get_package_manager(app);
+        if pkg_manager == "pnpm" {
+            format!("pnpm --filter {name} run {script}")
+        } else if pkg_manager == "yarn" {
+            format!("{pkg_manager} workspace {name} run {script}")
+        } else if pkg_manager == "bun" {
+            format!("bun run --filter {name} {script}")
+        } else {
+            format!("{pkg_manager} --workspace {name} run {script}")
+        }
+    }
+
     pub fn get_install_command(app: &App) -> Option<String> {
         if !app.includes_file("package.json") {
             return None;
@@ -466,7 +849,9 @@ impl NodeProvider {
         } else if package_manager == "yarn" {
             // TODO: When using Corepack and modern Yarn, we may not have a .yarnrc.yml - need to
             //       read the Yarn version from stdout after enabling Corepack.
-            if app.includes_file(".yarnrc.yml") {
+            if let Some(pnp_install_cmd) = YarnPnp::get_install_cmd(app) {
+                install_cmd = pnp_install_cmd;
+            } else if app.includes_file(".yarnrc.yml") {
                 install_cmd = "yarn install --check-cache".to_string();
             } else {
                 install_cmd = "yarn install --frozen-lockfile".to_string();
@@ -480,14 +865,117 @@ impl NodeProvider {
         Some(install_cmd)
     }
 
+    /// Workspace and prune install commands end with the package manager's install, so the flag
+    /// can be appended. Rush installs can't skip scripts.
+    fn get_ignore_scripts_install_cmd(app: &App, env: &Environment, install_cmd: String) -> String {
+        let is_yarn_berry =
+            NodeProvider::get_package_manager(app) == "yarn" && app.includes_file(".yarnrc.yml");
+        if is_yarn_berry || Rush::is_rush_repo(app, env) {
+            install_cmd
+        } else {
+            format!("{install_cmd} --ignore-scripts")
+        }
+    }
+
+    /// With install scripts disabled, only the trusted dependencies are rebuilt, followed by the
+    /// project's own `postinstall` script
+    fn get_postinstall_cmds(app: &App, env: &Environment) -> Result<Vec<String>> {
+        if !env.is_config_variable_truthy(IGNORE_SCRIPTS_ENV_VAR) {
+            return Ok(Vec::new());
+        }
+
+        let package_manager = NodeProvider::get_package_manager(app);
+        let mut cmds = Vec::new();
+        let trusted_dependencies = env
+            .get_config_variable(TRUSTED_DEPENDENCIES_ENV_VAR)
+            .map(|deps| {
+                deps.split(',')
+                    .map(str::trim)
+                    .filter(|dep| !dep.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .filter(|deps| !deps.is_empty());
+        if let Some(deps) = trusted_dependencies {
+            let rebuild_cmd = match package_manager.as_str() {
+                "pnpm" => "pnpm rebuild",
+                "bun" => "bun pm trust",
+                "yarn" if app.includes_file(".yarnrc.yml") => "yarn rebuild",
+                _ => "npm rebuild",
+            };
+            cmds.push(format!("{rebuild_cmd} {deps}"));
+        }
+
+        if NodeProvider::has_script(app, "postinstall")? {
+            cmds.push(format!("{package_manager} run postinstall"));
+        }
+
+        Ok(cmds)
+    }
+
+    /// Downloads pnpm packages into the store with only the lockfile copied, so the layer is
+    /// reused until pnpm-lock.yaml changes. The install phase then links them offline. The store
+    /// is kept in the layer rather than a cache directory, which could be gone by the install.
+    fn get_pnpm_fetch_phase(app: &App, env: &Environment) -> Option<Phase> {
+        if !env.is_config_variable_truthy(PNPM_FETCH_ENV_VAR)
+            || NodeProvider::get_package_manager(app) != "pnpm"
+            || !app.includes_file("pnpm-lock.yaml")
+        {
+            return None;
+        }
+
+        let mut fetch = Phase::new("fetch");
+        fetch.depends_on_phase("setup");
+        fetch.add_file_dependency("pnpm-lock.yaml");
+        if app.includes_file(".npmrc") {
+            fetch.add_file_dependency(".npmrc");
+        }
+        NpmRegistry::add_authenticated_cmd(&mut fetch, app, env, "pnpm fetch --frozen-lockfile");
+
+        Some(fetch)
+    }
+
+    /// Installs only production dependencies, from the lockfile when there is one
+    pub fn get_prod_install_command(app: &App) -> String {
+        let package_manager = NodeProvider::get_package_manager(app);
+        if package_manager == "pnpm" {
+            "pnpm i --prod --frozen-lockfile".to_string()
+        } else if package_manager == "yarn" {
+            "yarn install --production --frozen-lockfile".to_string()
+        } else if package_manager == "bun" {
+            "bun i --production".to_string()
+        } else {
+            "npm ci --omit=dev".to_string()
+        }
+    }
+
+    /// Removes devDependencies from node_modules once the build no longer needs them
+    pub fn get_prune_command(app: &App) -> String {
+        let package_manager = NodeProvider::get_package_manager(app);
+        if package_manager == "pnpm" {
+            "pnpm prune --prod".to_string()
+        } else if package_manager == "yarn" {
+            if app.includes_file(".yarnrc.yml") {
+                "yarn workspaces focus --all --production".to_string()
+            } else {
+                "yarn install --production --frozen-lockfile --ignore-scripts --prefer-offline"
+                    .to_string()
+            }
+        } else if package_manager == "bun" {
+            "bun install --production --ignore-scripts".to_string()
+        } else {
+            "npm prune --omit=dev".to_string()
+        }
+    }
+
     fn get_package_manager_cache_dir(app: &App) -> String {
         let package_manager = NodeProvider::get_package_manager(app);
         if package_manager == "yarn" {
-            (*YARN_CACHE_DIR).to_string()
+            YarnPnp::get_cache_dir(app).unwrap_or((*YARN_CACHE_DIR).to_string())
         } else if package_manager == "pnpm" {
             (*PNPM_CACHE_DIR).to_string()
         } else if package_manager == "bun" {
-            (*BUN_CACHE_DIR).to_string()
+            Bun::get_cache_dir(app).unwrap_or((*BUN_CACHE_DIR).to_string())
         } else {
             (*NPM_CACHE_DIR).to_string()
         }
@@ -497,6 +985,9 @@ impl NodeProvider {
         let package_manager = NodeProvider::get_package_manager(app);
         if package_manager == *"bun" {
             "bun"
+        } else if YarnPnp::is_pnp(app) {
+            // Loads the PnP runtime so packages resolve without node_modules
+            "yarn node"
         } else {
             "node"
         }
@@ -593,20 +1084,32 @@ impl NodeProvider {
                 pm_pkg = Pkg::new("npm-9_x");
             }
         }
-        pkgs.push(pm_pkg.from_overlay(NODE_OVERLAY));
+        // Corepack provides the package manager version pinned in package.json instead
+        if !NodeProvider::uses_corepack(app, env)? {
+            pkgs.push(pm_pkg.from_overlay(NODE_OVERLAY));
+        }
 
         Ok(pkgs)
     }
 
+    /// Whether any package.json or lockfile lists the package. Names are matched exactly, so
+    /// `bcryptjs` doesn't count as `bcrypt`.
     pub fn uses_node_dependency(app: &App, dependency: &str) -> bool {
-        [
-            "package.json",
-            "package-lock.json",
-            "yarn.lock",
-            "pnpm-lock.yaml",
-        ]
-        .iter()
-        .any(|file| app.read_file(file).unwrap_or_default().contains(dependency))
+        if NodeProvider::get_all_deps(app).is_ok_and(|deps| deps.contains(dependency)) {
+            return true;
+        }
+
+        // `node_modules/<name>` keys in package-lock.json, and `<name>@<version>` entries in
+        // yarn.lock and pnpm-lock.yaml (`/<name>@<version>` before pnpm 9)
+        let package_lock_key = format!("node_modules/{dependency}\"");
+        let lock_entry =
+            Regex::new(&format!(r#"(?m)^\s*['"]?/?{}@"#, regex::escape(dependency))).unwrap();
+        app.read_file("package-lock.json")
+            .unwrap_or_default()
+            .contains(&package_lock_key)
+            || ["yarn.lock", "pnpm-lock.yaml"]
+                .iter()
+                .any(|file| lock_entry.is_match(&app.read_file(file).unwrap_or_default()))
     }
 
     pub fn find_next_packages(app: &App) -> Result<Vec<String>> {
@@ -733,6 +1236,10 @@ impl NodeProvider {
     }
 }
 
+fn is_nx_monorepo(nx: Option<&Nx>, app: &App, env: &Environment) -> bool {
+    nx.is_some_and(|nx| nx.is_nx_monorepo(app, env))
+}
+
 fn version_number_to_archive(version: u32) -> Option<&'static str> {
     AVAILABLE_NODE_VERSIONS
         .iter()
@@ -805,6 +1312,15 @@ fn parse_nvmrc(nvmrc_content: &str) -> String {
         .to_string()
 }
 
+/// asdf and mise versions are plain versions, or `lts` for the latest LTS release
+fn parse_mise_version(version: &str) -> String {
+    if version == "lts" {
+        parse_nvmrc("lts/*")
+    } else {
+        parse_nvmrc(version)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::BTreeMap;
@@ -1136,6 +1652,40 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_version_from_tool_versions() -> Result<()> {
+        assert_eq!(
+            NodeProvider::get_nix_node_pkg(
+                &PackageJson {
+                    name: Some(String::default()),
+                    ..Default::default()
+                },
+                &App::new("examples/node-tool-versions")?,
+                &Environment::default()
+            )?,
+            Pkg::new("nodejs_20")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_from_mise() -> Result<()> {
+        assert_eq!(
+            NodeProvider::get_nix_node_pkg(
+                &PackageJson {
+                    name: Some(String::default()),
+                    ..Default::default()
+                },
+                &App::new("examples/node-mise")?,
+                &Environment::default()
+            )?,
+            Pkg::new("nodejs_22")
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_version_from_nvmrc_lts() -> Result<()> {
         assert_eq!(
@@ -1206,6 +1756,262 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_corepack_pins_package_manager() -> Result<()> {
+        let app = App::new("examples/node-pnpm-monorepo")?;
+        let env = Environment::default();
+
+        assert_eq!(
+            NodeProvider::get_corepack_setup_cmd(&app, &env)?,
+            Some(
+                "npm install -g corepack@0.24.1 && corepack enable && corepack prepare pnpm@9.5.0 --activate"
+                    .to_string()
+            )
+        );
+        assert!(!NodeProvider::get_nix_packages(&app, &env)?
+            .iter()
+            .any(|pkg| pkg.name.starts_with("pnpm")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_standalone() -> Result<()> {
+        let app = App::new("examples/node-next-standalone")?;
+        let env = Environment::default();
+
+        assert!(NodeProvider::is_next_standalone(&app, &env, None));
+        assert_eq!(
+            NodeProvider::get_start_cmd(&app, &env, None)?,
+            Some("node .next/standalone/server.js".to_string())
+        );
+
+        let plan = NodeProvider {}.get_build_plan(&app, &env)?.unwrap();
+        let start = plan.start_phase.unwrap();
+        assert_eq!(start.run_image, Some("node:20-slim".to_string()));
+        assert_eq!(
+            start.only_include_files,
+            Some(vec![".next/standalone".to_string()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_custom_start_script() -> Result<()> {
+        let app = App::new("examples/node-next-custom-server")?;
+        let env = Environment::default();
+
+        assert!(NodeProvider::is_next_standalone(&app, &env, None));
+        assert_eq!(
+            NodeProvider::get_start_cmd(&app, &env, None)?,
+            Some("npm run start".to_string())
+        );
+
+        let plan = NodeProvider {}.get_build_plan(&app, &env)?.unwrap();
+        let start = plan.start_phase.unwrap();
+        assert_eq!(start.run_image, None);
+        assert_eq!(start.only_include_files, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_dependency() {
+        let package_json = PackageJson {
+            dependencies: Some(HashMap::from([("next".to_string(), "14.2.5".to_string())])),
+            dev_dependencies: Some(HashMap::from([("prisma".to_string(), "5.0.0".to_string())])),
+            ..Default::default()
+        };
+
+        assert!(package_json.has_dependency("next"));
+        assert!(package_json.has_dependency("prisma"));
+        assert!(!package_json.has_dependency("react"));
+    }
+
+    #[test]
+    fn test_nx_cloud_token_not_in_plan() -> Result<()> {
+        let app = App::new("examples/node-nx-cloud")?;
+        let env = Environment::new(BTreeMap::from([(
+            "NX_CLOUD_ACCESS_TOKEN".to_string(),
+            "secret".to_string(),
+        )]));
+        let plan = NodeProvider {}.get_build_plan(&app, &env)?.unwrap();
+
+        assert!(!plan
+            .variables
+            .unwrap_or_default()
+            .contains_key("NX_CLOUD_ACCESS_TOKEN"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_phase() -> Result<()> {
+        let app = App::new("examples/node-prisma")?;
+        let plan = NodeProvider {}
+            .get_build_plan(&app, &Environment::default())?
+            .unwrap();
+        assert!(plan.get_phase("prune").is_none());
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NODE_PRUNE".to_string(),
+            "1".to_string(),
+        )]));
+        let plan = NodeProvider {}.get_build_plan(&app, &env)?.unwrap();
+        let prune = plan.get_phase("prune").unwrap();
+        assert_eq!(prune.cmds, Some(vec!["npm prune --omit=dev".to_string()]));
+        assert_eq!(prune.depends_on, Some(vec!["build".to_string()]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_limit() -> Result<()> {
+        assert!(
+            NodeProvider::get_memory_environment_variables(&Environment::default())?.is_empty()
+        );
+
+        let env = Environment::new(BTreeMap::from([
+            ("NIXPACKS_MEMORY_LIMIT_MB".to_string(), "2048".to_string()),
+            (
+                "NODE_OPTIONS".to_string(),
+                "--enable-source-maps".to_string(),
+            ),
+        ]));
+        assert_eq!(
+            NodeProvider::get_memory_environment_variables(&env)?.get("NODE_OPTIONS"),
+            Some(&"--enable-source-maps --max-old-space-size=1536".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_MEMORY_LIMIT_MB".to_string(),
+            "2gb".to_string(),
+        )]));
+        assert!(NodeProvider::get_memory_environment_variables(&env).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_scripts() -> Result<()> {
+        let app = App::new("examples/node-nuxt")?;
+        let env = Environment::new(BTreeMap::from([
+            ("NIXPACKS_NODE_IGNORE_SCRIPTS".to_string(), "1".to_string()),
+            (
+                "NIXPACKS_NODE_TRUSTED_DEPENDENCIES".to_string(),
+                "esbuild, @parcel/watcher".to_string(),
+            ),
+        ]));
+        let plan = NodeProvider {}.get_build_plan(&app, &env)?.unwrap();
+        let install = plan.get_phase("install").unwrap();
+        assert_eq!(
+            install.cmds,
+            Some(vec![
+                "npm i --ignore-scripts".to_string(),
+                "npm rebuild esbuild @parcel/watcher".to_string(),
+                "npm run postinstall".to_string()
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_auth_only_during_install() -> Result<()> {
+        let app = App::new("examples/node-vite-react-ts")?;
+        let plan = NodeProvider {}
+            .get_build_plan(
+                &app,
+                &Environment::new(BTreeMap::from([(
+                    "NPM_TOKEN".to_string(),
+                    "secret".to_string(),
+                )])),
+            )?
+            .unwrap();
+
+        let setup = plan.get_phase("setup").unwrap();
+        assert!(!setup
+            .cmds
+            .as_ref()
+            .is_some_and(|cmds| cmds.iter().any(|cmd| cmd.contains(".npmrc"))));
+
+        let install = plan.get_phase("install").unwrap();
+        assert_eq!(
+            install.cmds,
+            Some(vec![
+                "printf '%s\\n' '//registry.npmjs.org/:_authToken=${NPM_TOKEN}' > /root/.npmrc"
+                    .to_string(),
+                "npm i".to_string(),
+                "rm -f /root/.npmrc".to_string()
+            ])
+        );
+
+        // Pruning can download packages too, e.g. with yarn v1
+        let plan = NodeProvider {}
+            .get_build_plan(
+                &app,
+                &Environment::new(BTreeMap::from([
+                    ("NPM_TOKEN".to_string(), "secret".to_string()),
+                    ("NIXPACKS_NODE_PRUNE".to_string(), "true".to_string()),
+                ])),
+            )?
+            .unwrap();
+        let prune = plan.get_phase("prune").unwrap();
+        assert_eq!(
+            prune.cmds,
+            Some(vec![
+                "printf '%s\\n' '//registry.npmjs.org/:_authToken=${NPM_TOKEN}' > /root/.npmrc"
+                    .to_string(),
+                "npm prune --omit=dev".to_string(),
+                "rm -f /root/.npmrc".to_string()
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_pnpm_fetch_phase() -> Result<()> {
+        let app = App::new("examples/node-pnpm-monorepo")?;
+        let plan = NodeProvider {}
+            .get_build_plan(&app, &Environment::default())?
+            .unwrap();
+        assert!(plan.get_phase("fetch").is_none());
+
+        let plan = NodeProvider {}
+            .get_build_plan(
+                &app,
+                &Environment::new(BTreeMap::from([(
+                    "NIXPACKS_PNPM_FETCH".to_string(),
+                    "1".to_string(),
+                )])),
+            )?
+            .unwrap();
+
+        let fetch = plan.get_phase("fetch").unwrap();
+        assert_eq!(
+            fetch.only_include_files,
+            Some(vec!["pnpm-lock.yaml".to_string()])
+        );
+        assert_eq!(
+            fetch.cmds,
+            Some(vec!["pnpm fetch --frozen-lockfile".to_string()])
+        );
+
+        let install = plan.get_phase("install").unwrap();
+        assert_eq!(
+            install.cmds,
+            Some(vec!["pnpm i --offline --frozen-lockfile".to_string()])
+        );
+        assert!(install
+            .depends_on
+            .as_ref()
+            .is_some_and(|phases| phases.contains(&"fetch".to_string())));
+
+        Ok(())
+    }
+
     #[test]
     fn test_correct_package_manager_monorepo_root() -> Result<()> {
         assert_eq!(
@@ -1,8 +1,39 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
 use crate::{
     nixpacks::{app::App, environment::Environment},
     providers::node::NodeProvider,
 };
 
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct MoonWorkspace {
+    pub projects: Option<MoonProjects>,
+}
+
+/// Projects are listed by globs, by id and source path, or both.
+/// `Sources` has to come before `Config`, which would otherwise match any map.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MoonProjects {
+    Globs(Vec<String>),
+    Sources(BTreeMap<String, String>),
+    Config {
+        #[serde(default)]
+        globs: Vec<String>,
+        #[serde(default)]
+        sources: BTreeMap<String, String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct MoonProject {
+    pub id: Option<String>,
+    pub tasks: Option<BTreeMap<String, Value>>,
+}
+
 const MOON_APP_NAME_ENV_VAR: &str = "MOON_APP_NAME";
 const MOON_BUILD_TASK_ENV_VAR: &str = "MOON_BUILD_TASK";
 const MOON_START_TASK_ENV_VAR: &str = "MOON_START_TASK";
@@ -11,11 +42,78 @@ pub struct Moon;
 
 impl Moon {
     pub fn is_moon_repo(app: &App, env: &Environment) -> bool {
-        Moon::get_moon_app_name(app, env).is_some() && app.includes_file(".moon/workspace.yml")
+        app.includes_file(".moon/workspace.yml") && Moon::get_moon_app_name(app, env).is_some()
+    }
+
+    /// The project set with `MOON_APP_NAME`, or the only project with a start task
+    pub fn get_moon_app_name(app: &App, env: &Environment) -> Option<String> {
+        if let Some(app_name) = env.get_config_variable(MOON_APP_NAME_ENV_VAR) {
+            return Some(app_name);
+        }
+
+        let start_task = env
+            .get_config_variable(MOON_START_TASK_ENV_VAR)
+            .unwrap_or("start".to_string());
+        let mut app_names: Vec<String> = Moon::get_projects(app)
+            .into_iter()
+            .filter(|(_, project)| {
+                project
+                    .tasks
+                    .as_ref()
+                    .is_some_and(|tasks| tasks.contains_key(&start_task))
+            })
+            .map(|(id, _)| id)
+            .collect();
+
+        if app_names.len() == 1 {
+            app_names.pop()
+        } else {
+            None
+        }
     }
 
-    pub fn get_moon_app_name(_app: &App, env: &Environment) -> Option<String> {
-        env.get_config_variable(MOON_APP_NAME_ENV_VAR)
+    /// Reads the projects from `.moon/workspace.yml`, keyed by their id
+    pub fn get_projects(app: &App) -> BTreeMap<String, MoonProject> {
+        let mut projects = BTreeMap::new();
+        let workspace: MoonWorkspace = app.read_yaml(".moon/workspace.yml").unwrap_or_default();
+        let (globs, sources) = match workspace.projects {
+            Some(MoonProjects::Globs(globs)) => (globs, BTreeMap::new()),
+            Some(MoonProjects::Config { globs, sources }) => (globs, sources),
+            Some(MoonProjects::Sources(sources)) => (Vec::new(), sources),
+            None => (Vec::new(), BTreeMap::new()),
+        };
+
+        for (id, source) in sources {
+            let project: MoonProject = app
+                .read_yaml(&format!("{}/moon.yml", source.trim_end_matches('/')))
+                .unwrap_or_default();
+            projects.insert(id, project);
+        }
+
+        for glob in globs {
+            for dir in app.find_directories(&glob).unwrap_or_default() {
+                let relative = match app.strip_source_path(dir.as_path()) {
+                    Ok(relative) => relative,
+                    Err(_) => continue,
+                };
+                let project: MoonProject =
+                    match app.read_yaml(&format!("{}/moon.yml", relative.display())) {
+                        Ok(project) => project,
+                        Err(_) => continue,
+                    };
+
+                // Projects found by globs are named after their folder unless they set an id
+                if let Some(id) = project
+                    .id
+                    .clone()
+                    .or_else(|| dir.file_name()?.to_str().map(String::from))
+                {
+                    projects.entry(id).or_insert(project);
+                }
+            }
+        }
+
+        projects
     }
 
     pub fn get_build_cmd(app: &App, env: &Environment) -> String {
@@ -44,3 +142,44 @@ impl Moon {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_moon() {
+        let app = App::new("examples/node-moon").unwrap();
+        let env = Environment::default();
+
+        assert!(Moon::is_moon_repo(&app, &env));
+        assert_eq!(
+            Moon::get_projects(&app).keys().collect::<Vec<_>>(),
+            vec!["server", "utils"]
+        );
+        assert_eq!(
+            Moon::get_build_cmd(&app, &env),
+            "npx @moonrepo/cli run server:build"
+        );
+        assert_eq!(
+            Moon::get_start_cmd(&app, &env),
+            "npx @moonrepo/cli run server:start"
+        );
+    }
+
+    #[test]
+    fn test_moon_project_sources() {
+        let app = App::new("examples/node-moon-sources").unwrap();
+        let env = Environment::default();
+
+        assert_eq!(
+            Moon::get_projects(&app).keys().collect::<Vec<_>>(),
+            vec!["shared", "web"]
+        );
+        assert_eq!(Moon::get_moon_app_name(&app, &env), Some("web".to_string()));
+        assert_eq!(
+            Moon::get_build_cmd(&app, &env),
+            "npx @moonrepo/cli run web:build"
+        );
+    }
+}
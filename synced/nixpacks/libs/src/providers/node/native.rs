@@ -0,0 +1,126 @@
+// Code relating to npm packages with native addons that need system libraries
+
+use crate::nixpacks::{app::App, nix::pkg::Pkg};
+
+use super::NodeProvider;
+
+/// The Nix packages and libraries a native module needs to build and load
+struct NativeModule {
+    dependency: &'static str,
+    nix_pkgs: &'static [&'static str],
+    nix_libs: &'static [&'static str],
+}
+
+const NATIVE_MODULES: &[NativeModule] = &[
+    NativeModule {
+        dependency: "sharp",
+        // sharp bundles libvips, and a global libvips makes sharp 0.33+ build from source
+        nix_pkgs: &[],
+        nix_libs: &["gcc-unwrapped"],
+    },
+    NativeModule {
+        dependency: "canvas",
+        nix_pkgs: &[
+            "cairo",
+            "pango",
+            "libjpeg",
+            "giflib",
+            "librsvg",
+            "pixman",
+            "pkg-config",
+        ],
+        nix_libs: &["libuuid", "libGL"],
+    },
+    NativeModule {
+        dependency: "pg-native",
+        nix_pkgs: &["postgresql"],
+        nix_libs: &[],
+    },
+    NativeModule {
+        dependency: "better-sqlite3",
+        nix_pkgs: &["python3", "gnumake", "gcc"],
+        nix_libs: &[],
+    },
+    NativeModule {
+        dependency: "bcrypt",
+        nix_pkgs: &["python3", "gnumake", "gcc"],
+        nix_libs: &[],
+    },
+];
+
+pub struct NativeModules;
+
+impl NativeModules {
+    fn find_native_modules(app: &App) -> impl Iterator<Item = &'static NativeModule> + '_ {
+        NATIVE_MODULES
+            .iter()
+            .filter(|module| NodeProvider::uses_node_dependency(app, module.dependency))
+    }
+
+    pub fn get_nix_pkgs(app: &App) -> Vec<Pkg> {
+        let mut pkgs: Vec<Pkg> = Vec::new();
+        for name in NativeModules::find_native_modules(app).flat_map(|module| module.nix_pkgs) {
+            if !pkgs.iter().any(|pkg| pkg.name == *name) {
+                pkgs.push(Pkg::new(name));
+            }
+        }
+
+        pkgs
+    }
+
+    pub fn get_nix_libs(app: &App) -> Vec<String> {
+        let mut libs: Vec<String> = Vec::new();
+        for name in NativeModules::find_native_modules(app).flat_map(|module| module.nix_libs) {
+            if !libs.iter().any(|lib| lib == name) {
+                libs.push((*name).to_string());
+            }
+        }
+
+        libs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_native_modules() {
+        let app = App::new("examples/node-native-modules").unwrap();
+        let pkgs: Vec<String> = NativeModules::get_nix_pkgs(&app)
+            .into_iter()
+            .map(|pkg| pkg.name)
+            .collect();
+        assert_eq!(
+            pkgs,
+            vec![
+                "cairo",
+                "pango",
+                "libjpeg",
+                "giflib",
+                "librsvg",
+                "pixman",
+                "pkg-config",
+                "postgresql"
+            ]
+        );
+        assert_eq!(
+            NativeModules::get_nix_libs(&app),
+            vec!["gcc-unwrapped", "libuuid", "libGL"]
+        );
+    }
+
+    #[test]
+    fn test_similar_names() {
+        let app = App::new("examples/node-native-modules-lookalikes").unwrap();
+        assert!(NativeModules::get_nix_pkgs(&app).is_empty());
+        assert!(NativeModules::get_nix_libs(&app).is_empty());
+    }
+
+    #[test]
+    fn test_no_native_modules() {
+        let app = App::new("examples/node-prisma").unwrap();
+        assert!(NativeModules::get_nix_pkgs(&app).is_empty());
+        assert!(NativeModules::get_nix_libs(&app).is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+// Code relating to Next.js apps
+
+use regex::Regex;
+
+use crate::nixpacks::app::App;
+
+const NEXT_CONFIG_FILES: &[&str] = &[
+    "next.config.js",
+    "next.config.mjs",
+    "next.config.cjs",
+    "next.config.ts",
+];
+
+pub struct Next;
+
+impl Next {
+    /// Whether the next.config in `dir` builds a standalone server with `output: "standalone"`
+    pub fn is_standalone(app: &App, dir: &str) -> bool {
+        let r = Regex::new(r#"output:\s*['"`]standalone['"`]"#).unwrap();
+        NEXT_CONFIG_FILES.iter().any(|config_file| {
+            app.read_file(&join_path(dir, config_file))
+                .is_ok_and(|config| r.is_match(&config))
+        })
+    }
+
+    /// The standalone server doesn't serve `.next/static` or `public` unless they are copied next to it
+    pub fn get_standalone_copy_cmds(next_dir: &str, public_dir: Option<&str>) -> Vec<String> {
+        let standalone_dir = Next::get_standalone_dir(next_dir);
+        let mut cmds = vec![format!(
+            "cp -r {} {standalone_dir}/.next/static",
+            join_path(next_dir, ".next/static")
+        )];
+        if let Some(public_dir) = public_dir {
+            cmds.push(format!("cp -r {public_dir} {standalone_dir}/public"));
+        }
+
+        cmds
+    }
+
+    pub fn get_standalone_start_cmd(next_dir: &str) -> String {
+        format!("node {}/server.js", Next::get_standalone_dir(next_dir))
+    }
+
+    fn get_standalone_dir(next_dir: &str) -> String {
+        join_path(next_dir, ".next/standalone")
+    }
+}
+
+fn join_path(dir: &str, path: &str) -> String {
+    if dir.is_empty() || dir == "." {
+        path.to_string()
+    } else {
+        format!("{}/{path}", dir.trim_end_matches('/'))
+    }
+}
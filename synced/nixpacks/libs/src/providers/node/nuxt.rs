@@ -0,0 +1,145 @@
+// Code relating to Nuxt 3 apps
+
+use node_semver::Range;
+use regex::Regex;
+
+use crate::nixpacks::{app::App, environment::Environment};
+
+use super::PackageJson;
+
+const NUXT_CONFIG_FILES: &[&str] = &["nuxt.config.ts", "nuxt.config.js", "nuxt.config.mjs"];
+const NITRO_PRESET_ENV_VAR: &str = "NITRO_PRESET";
+const NODE_PRESETS: &[&str] = &["node-server", "node-cluster"];
+const STATIC_PRESET: &str = "static";
+
+pub struct Nuxt;
+
+impl Nuxt {
+    pub fn is_nuxt(app: &App) -> bool {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        package_json.has_dependency("nuxt")
+            && Nuxt::uses_nitro(&package_json)
+            && NUXT_CONFIG_FILES
+                .iter()
+                .any(|config_file| app.includes_file(config_file))
+    }
+
+    /// Only Nuxt 3+ builds a Nitro server to `.output`, Nuxt 2 apps keep using their start script.
+    /// Versions that aren't a range (e.g. `latest`) are assumed to be current.
+    fn uses_nitro(package_json: &PackageJson) -> bool {
+        let version = [&package_json.dependencies, &package_json.dev_dependencies]
+            .into_iter()
+            .flatten()
+            .find_map(|deps| deps.get("nuxt"));
+
+        match version.and_then(|version| Range::parse(version).ok()) {
+            Some(range) => range.allows_any(&Range::parse(">=3").unwrap()),
+            None => true,
+        }
+    }
+
+    /// The Nitro preset from `NITRO_PRESET`, or `nitro.preset` in nuxt.config
+    pub fn get_preset(app: &App, env: &Environment) -> String {
+        if let Some(preset) = env.get_variable(NITRO_PRESET_ENV_VAR) {
+            return preset.to_string();
+        }
+
+        let r = Regex::new(r#"\bpreset:\s*['"`](.*?)['"`]"#).unwrap();
+        NUXT_CONFIG_FILES
+            .iter()
+            .find_map(|config_file| app.read_file(config_file).ok())
+            .and_then(|config| {
+                r.captures(&config)
+                    .and_then(|c| c.get(1))
+                    .map(|preset| preset.as_str().to_string())
+            })
+            .unwrap_or(NODE_PRESETS[0].to_string())
+    }
+
+    /// Other presets build for a hosting platform or export a handler, so there is no server to start
+    pub fn get_start_cmd(app: &App, env: &Environment) -> Option<String> {
+        if !Nuxt::is_nuxt(app) {
+            return None;
+        }
+
+        let preset = Nuxt::get_preset(app, env);
+        if !NODE_PRESETS.contains(&preset.as_str()) {
+            if preset != STATIC_PRESET {
+                eprintln!("Warning: Nitro preset `{preset}` does not start a Node server, so no start command is set");
+            }
+            return None;
+        }
+
+        Some("node .output/server/index.mjs".to_string())
+    }
+
+    /// The static preset prerenders the site to `.output/public`, which is served like a SPA
+    pub fn get_static_output_dir(app: &App, env: &Environment) -> Option<String> {
+        if !Nuxt::is_nuxt(app) || Nuxt::get_preset(app, env) != STATIC_PRESET {
+            return None;
+        }
+
+        Some(".output/public".to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::providers::node::NodeProvider;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_nuxt() {
+        let app = App::new("examples/node-nuxt").unwrap();
+        let env = Environment::default();
+
+        assert!(Nuxt::is_nuxt(&app));
+        assert_eq!(
+            Nuxt::get_start_cmd(&app, &env),
+            Some("node .output/server/index.mjs".to_string())
+        );
+        assert_eq!(Nuxt::get_static_output_dir(&app, &env), None);
+    }
+
+    #[test]
+    fn test_nuxt_2() {
+        let app = App::new("examples/node-nuxt-2").unwrap();
+        let env = Environment::default();
+
+        assert!(!Nuxt::is_nuxt(&app));
+        assert_eq!(Nuxt::get_start_cmd(&app, &env), None);
+        assert_eq!(
+            NodeProvider::get_start_cmd(&app, &env, None).unwrap(),
+            Some("npm run start".to_string())
+        );
+    }
+
+    #[test]
+    fn test_nuxt_static_preset() {
+        let app = App::new("examples/node-nuxt").unwrap();
+        let env = Environment::new(BTreeMap::from([(
+            "NITRO_PRESET".to_string(),
+            "static".to_string(),
+        )]));
+
+        assert_eq!(Nuxt::get_start_cmd(&app, &env), None);
+        assert_eq!(
+            Nuxt::get_static_output_dir(&app, &env),
+            Some(".output/public".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NITRO_PRESET".to_string(),
+            "vercel".to_string(),
+        )]));
+        assert_eq!(Nuxt::get_start_cmd(&app, &env), None);
+        assert_eq!(Nuxt::get_static_output_dir(&app, &env), None);
+
+        let env = Environment::new(BTreeMap::from([(
+            "NITRO_PRESET".to_string(),
+            "node".to_string(),
+        )]));
+        assert_eq!(Nuxt::get_start_cmd(&app, &env), None);
+    }
+}
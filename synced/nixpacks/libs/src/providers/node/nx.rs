@@ -1,202 +1,1856 @@
 // Code relating to NX Monorepos
 
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use anyhow::Result;
+use path_slash::PathExt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::nixpacks::{app::App, environment::Environment};
-use crate::providers::node::NodeProvider;
+use crate::nixpacks::{app::App, environment::Environment, plan::merge::Mergeable};
+use crate::providers::node::{next::Next, registry::NpmRegistry, NodeProvider};
 
 #[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
 pub struct NxJson {
     #[serde(alias = "defaultProject")]
     pub default_project: Option<String>,
+    #[serde(alias = "workspaceLayout")]
+    pub workspace_layout: Option<WorkspaceLayout>,
+    #[serde(alias = "targetDefaults")]
+    pub target_defaults: Option<BTreeMap<String, Target>>,
+    #[serde(alias = "nxCloudAccessToken")]
+    pub nx_cloud_access_token: Option<String>,
+    #[serde(alias = "nxCloudId")]
+    pub nx_cloud_id: Option<String>,
+    #[serde(alias = "tasksRunnerOptions")]
+    pub tasks_runner_options: Option<BTreeMap<String, TasksRunner>>,
+    pub plugins: Option<Vec<NxPlugin>>,
 }
 
+/// An entry of nx.json `plugins`, either the plugin name or `{ "plugin": ..., "options": ... }`
+#[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum NxPlugin {
+    Name(String),
+    Config {
+        plugin: String,
+        options: Option<BTreeMap<String, Value>>,
+    },
+}
+
+impl NxPlugin {
+    pub fn name(&self) -> &str {
+        match self {
+            NxPlugin::Name(name) => name,
+            NxPlugin::Config { plugin, .. } => plugin,
+        }
+    }
+
+    /// The name the plugin gives an inferred target, e.g. the `buildTargetName` option
+    fn target_name(&self, option: &str, default_name: &str) -> String {
+        if let NxPlugin::Config {
+            options: Some(options),
+            ..
+        } = self
+        {
+            if let Some(name) = options.get(option).and_then(Value::as_str) {
+                return name.to_string();
+            }
+        }
+
+        default_name.to_string()
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
+pub struct TasksRunner {
+    pub runner: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
+pub struct WorkspaceLayout {
+    #[serde(alias = "appsDir")]
+    pub apps_dir: Option<String>,
+    #[serde(alias = "libsDir")]
+    pub libs_dir: Option<String>,
+}
+
+/// The Nx configuration of a project, from either its project.json or the `nx` key of its package.json
 #[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
 pub struct ProjectJson {
-    pub targets: Targets,
+    pub name: Option<String>,
+    pub root: Option<String>,
+    #[serde(alias = "projectType")]
+    pub project_type: Option<String>,
+    // Angular CLI style configs call targets `architect`
+    #[serde(default, alias = "architect")]
+    pub targets: BTreeMap<String, Target>,
 }
 
+/// workspace.json, or angular.json in Angular CLI workspaces, from older Nx versions
 #[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
-pub struct Targets {
-    pub build: Target,
-    pub start: Option<Target>,
+pub struct WorkspaceJson {
+    #[serde(alias = "defaultProject")]
+    pub default_project: Option<String>,
+    #[serde(default)]
+    pub projects: BTreeMap<String, WorkspaceProject>,
 }
 
+/// A workspace.json project is either the path to its project.json folder or the inline configuration
 #[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum WorkspaceProject {
+    Path(String),
+    Config(ProjectJson),
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, Deserialize, Clone)]
 pub struct Target {
-    pub executor: String,
+    #[serde(alias = "builder")]
+    pub executor: Option<String>,
+    pub outputs: Option<Vec<String>>,
     pub options: Option<NxTargetOptions>,
     pub configurations: Option<Configuration>,
 }
 
-#[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Serialize, PartialEq, Eq, Deserialize, Clone)]
 pub struct NxTargetOptions {
     #[serde(alias = "outputPath")]
     pub output_path: Option<Value>,
     pub main: Option<String>,
+    pub ssr: Option<Value>,
+    pub server: Option<String>,
+    #[serde(alias = "outputFileName")]
+    pub output_file_name: Option<String>,
+    pub format: Option<Value>,
+    pub bundle: Option<bool>,
+    #[serde(alias = "esbuildOptions")]
+    pub esbuild_options: Option<Value>,
+    #[serde(alias = "generatePackageJson")]
+    pub generate_package_json: Option<bool>,
 }
 
-#[derive(Debug, Serialize, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Serialize, PartialEq, Eq, Deserialize, Clone)]
 pub struct Configuration {
     pub production: Option<Value>,
 }
 
-pub struct Nx {}
+impl Mergeable for Target {
+    fn merge(c1: &Target, c2: &Target) -> Target {
+        let target2 = c2.clone();
+        Target {
+            executor: target2.executor.or_else(|| c1.executor.clone()),
+            outputs: target2.outputs.or_else(|| c1.outputs.clone()),
+            options: merge_optional(&c1.options, &target2.options),
+            configurations: merge_optional(&c1.configurations, &target2.configurations),
+        }
+    }
+}
+
+impl Mergeable for NxTargetOptions {
+    fn merge(c1: &NxTargetOptions, c2: &NxTargetOptions) -> NxTargetOptions {
+        let options2 = c2.clone();
+        NxTargetOptions {
+            output_path: options2.output_path.or_else(|| c1.output_path.clone()),
+            main: options2.main.or_else(|| c1.main.clone()),
+            ssr: options2.ssr.or_else(|| c1.ssr.clone()),
+            server: options2.server.or_else(|| c1.server.clone()),
+            output_file_name: options2
+                .output_file_name
+                .or_else(|| c1.output_file_name.clone()),
+            format: options2.format.or_else(|| c1.format.clone()),
+            bundle: options2.bundle.or(c1.bundle),
+            esbuild_options: options2
+                .esbuild_options
+                .or_else(|| c1.esbuild_options.clone()),
+            generate_package_json: options2.generate_package_json.or(c1.generate_package_json),
+        }
+    }
+}
+
+impl Mergeable for Configuration {
+    fn merge(c1: &Configuration, c2: &Configuration) -> Configuration {
+        let configuration2 = c2.clone();
+        Configuration {
+            production: configuration2.production.or_else(|| c1.production.clone()),
+        }
+    }
+}
+
+fn merge_optional<T: Mergeable + Clone>(c1: &Option<T>, c2: &Option<T>) -> Option<T> {
+    match (c1, c2) {
+        (Some(c1), Some(c2)) => Some(T::merge(c1, c2)),
+        (c1, c2) => c2.clone().or_else(|| c1.clone()),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NxProject {
+    pub name: String,
+    pub root: String,
+    pub project_type: Option<String>,
+    pub targets: BTreeMap<String, Target>,
+}
+
+impl NxProject {
+    pub fn get_target(&self, target_name: &str) -> Option<&Target> {
+        self.targets.get(target_name)
+    }
+
+    pub fn is_application(&self) -> bool {
+        self.project_type.as_deref() != Some("library")
+    }
+}
+
+/// Every project in an Nx workspace, keyed by project name
+#[derive(Debug)]
+pub struct NxProjectGraph {
+    pub projects: BTreeMap<String, NxProject>,
+    pub apps_dir: String,
+    pub libs_dir: String,
+}
+
+impl NxProjectGraph {
+    pub fn new(app: &App) -> Result<NxProjectGraph> {
+        let nx_json = app.read_json::<NxJson>("nx.json").ok();
+        let (apps_dir, libs_dir) = Nx::get_workspace_layout(nx_json.as_ref());
+        let plugins = nx_json
+            .as_ref()
+            .and_then(|nx_json| nx_json.plugins.as_ref())
+            .map(|plugins| plugins.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let target_defaults = nx_json
+            .as_ref()
+            .and_then(|nx_json| nx_json.target_defaults.clone())
+            .unwrap_or_default();
+        let mut graph = NxProjectGraph {
+            projects: BTreeMap::new(),
+            apps_dir,
+            libs_dir,
+        };
+
+        for config_file in ["project.json", "package.json"] {
+            for file in app.find_files(&format!("**/{config_file}"))? {
+                if file
+                    .as_path()
+                    .to_str()
+                    .unwrap_or_default()
+                    .contains("node_modules")
+                {
+                    continue;
+                }
+
+                let relative = app.strip_source_path(file.as_path())?;
+                let root = match relative.parent() {
+                    // The package.json at the workspace root is not a project
+                    Some(root) if !root.as_os_str().is_empty() => root,
+                    _ => continue,
+                };
+                // Roots that aren't valid UTF-8 can't be passed to nx
+                let root_path = match root.to_slash() {
+                    Some(root_path) => root_path.into_owned(),
+                    None => continue,
+                };
+
+                // A project.json takes precedence over the package.json next to it
+                if config_file == "package.json"
+                    && app.includes_file(&format!("{root_path}/project.json"))
+                {
+                    continue;
+                }
+
+                let inferred_targets = Nx::get_inferred_targets(app, &root_path, &plugins);
+                // Plugins can infer every target of a plain package.json project
+                let mut project_json = match Nx::read_project_json(
+                    app,
+                    &root_path,
+                    config_file,
+                    !inferred_targets.is_empty(),
+                ) {
+                    Some(project_json) => project_json,
+                    None => continue,
+                };
+                Nx::apply_inferred_targets(&mut project_json.targets, inferred_targets);
+                Nx::apply_target_defaults(&mut project_json.targets, &target_defaults);
+
+                // The declared name is used for `nx run`, the folder name otherwise
+                let name = project_json
+                    .name
+                    .or_else(|| root.file_name().and_then(|n| n.to_str()).map(String::from));
+                if let Some(name) = name {
+                    graph.add_project(NxProject {
+                        name,
+                        root: root_path,
+                        project_type: project_json.project_type,
+                        targets: project_json.targets,
+                    });
+                }
+            }
+        }
+
+        // Projects listed in workspace.json are keyed by the name used for `nx run`
+        if let Some(workspace_json) = Nx::read_workspace_json(app) {
+            for (name, workspace_project) in workspace_json.projects {
+                let (root, mut project_json) = match workspace_project {
+                    WorkspaceProject::Path(root) => {
+                        match Nx::read_project_json(app, &root, "project.json", false) {
+                            Some(project_json) => (root, project_json),
+                            None => continue,
+                        }
+                    }
+                    WorkspaceProject::Config(project_json) => match project_json.root.clone() {
+                        Some(root) => (root, project_json),
+                        None => continue,
+                    },
+                };
+                let root = root.trim_end_matches('/').to_string();
+
+                let inferred_targets = Nx::get_inferred_targets(app, &root, &plugins);
+                Nx::apply_inferred_targets(&mut project_json.targets, inferred_targets);
+                Nx::apply_target_defaults(&mut project_json.targets, &target_defaults);
+
+                graph.replace_project(NxProject {
+                    name,
+                    root,
+                    project_type: project_json.project_type,
+                    targets: project_json.targets,
+                });
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Adds a project in place of any project found at the same root
+    fn replace_project(&mut self, project: NxProject) {
+        self.projects
+            .retain(|_, existing| existing.root != project.root);
+        self.projects.insert(project.name.clone(), project);
+    }
+
+    fn add_project(&mut self, project: NxProject) {
+        // Projects in the apps directory win over same-named projects elsewhere
+        let replace = match self.projects.get(&project.name) {
+            Some(existing) => {
+                !is_inside_dir(&existing.root, &self.apps_dir)
+                    && is_inside_dir(&project.root, &self.apps_dir)
+            }
+            None => true,
+        };
+
+        if replace {
+            self.projects.insert(project.name.clone(), project);
+        }
+    }
+
+    /// Finds a project by name or by its root, e.g. `apps/backend/api` or `backend/api`
+    pub fn get_project(&self, name: &str) -> Option<&NxProject> {
+        let path = name.trim_end_matches('/');
+        self.projects.get(name).or_else(|| {
+            self.projects.values().find(|project| {
+                project.root == path || project.root == format!("{}/{path}", self.apps_dir)
+            })
+        })
+    }
+
+    /// Suggests the project whose name is closest to a misspelled one
+    pub fn get_closest_project_name(&self, name: &str) -> Option<&str> {
+        self.projects
+            .keys()
+            .map(|project_name| (edit_distance(name, project_name), project_name))
+            .filter(|(distance, project_name)| *distance <= project_name.len().max(3) / 3)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, project_name)| project_name.as_str())
+    }
+
+    /// Picks the first buildable application, preferring projects inside the apps directory
+    pub fn detect_app_name(&self, build_target: &str) -> Option<String> {
+        let buildable_apps = || {
+            self.projects.values().filter(|project| {
+                project.is_application() && project.get_target(build_target).is_some()
+            })
+        };
+
+        buildable_apps()
+            .find(|project| is_inside_dir(&project.root, &self.apps_dir))
+            // Flat layouts have no apps directory, so take the first project that isn't a library
+            .or_else(|| {
+                buildable_apps().find(|project| !is_inside_dir(&project.root, &self.libs_dir))
+            })
+            .map(|project| project.name.clone())
+    }
+}
+
+/// Where the Nx app to build was picked from
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NxAppSource {
+    EnvVar,
+    DefaultProject,
+    AutoDetect,
+}
+
+impl NxAppSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NxAppSource::EnvVar => "env",
+            NxAppSource::DefaultProject => "default-project",
+            NxAppSource::AutoDetect => "auto-detect",
+        }
+    }
+}
+
+/// An Nx workspace, holding the project graph that every helper works from
+pub struct Nx {
+    pub graph: NxProjectGraph,
+}
 
 const NX_APP_NAME_ENV_VAR: &str = "NX_APP_NAME";
+const NX_APP_NAMES_ENV_VAR: &str = "NX_APP_NAMES";
+const NX_BASE_ENV_VAR: &str = "NX_BASE";
+const NX_CLOUD_ACCESS_TOKEN_ENV_VAR: &str = "NX_CLOUD_ACCESS_TOKEN";
+const NX_CONFIGURATION_ENV_VAR: &str = "NX_CONFIGURATION";
+const NX_RUN_TESTS_ENV_VAR: &str = "NX_RUN_TESTS";
+const NX_PRUNE_ENV_VAR: &str = "NX_PRUNE";
+const NX_BUILD_TARGET_ENV_VAR: &str = "NX_BUILD_TARGET";
+const NX_START_TARGET_ENV_VAR: &str = "NX_START_TARGET";
+const DEFAULT_BUILD_TARGET: &str = "build";
+const DEFAULT_START_TARGET: &str = "start";
+const DEFAULT_TEST_TARGET: &str = "test";
+const DEFAULT_APPS_DIR: &str = "apps";
+const ANGULAR_BROWSER_EXECUTORS: &[&str] = &[
+    "@angular-devkit/build-angular:browser",
+    "@angular-devkit/build-angular:browser-esbuild",
+    "@nx/angular:webpack-browser",
+    "@nx/angular:browser-esbuild",
+];
+const ANGULAR_APPLICATION_EXECUTORS: &[&str] = &[
+    "@angular-devkit/build-angular:application",
+    "@angular/build:application",
+    "@nx/angular:application",
+];
+const VITE_EXECUTORS: &[&str] = &["@nx/vite:build", "@nrwl/vite:build"];
+const REMIX_EXECUTORS: &[&str] = &["@nx/remix:build", "@nrwl/remix:build"];
+const NUXT_EXECUTORS: &[&str] = &["@nx/nuxt:build"];
+const DEFAULT_LIBS_DIR: &str = "libs";
 
 impl Nx {
-    pub fn is_nx_monorepo(app: &App, env: &Environment) -> bool {
-        // Only consider an Nx app if an nx app name and project path can be found
-        if let Some(nx_app_name) = Nx::get_nx_app_name(app, env) {
-            return app.includes_file("nx.json")
-                && Nx::get_nx_project_json_for_app(app, &nx_app_name).is_ok();
+    /// Reads the workspace's project graph, which walks the whole repo, so it is only done once per plan
+    pub fn new(app: &App) -> Option<Nx> {
+        if !app.includes_file("nx.json") {
+            return None;
         }
 
-        false
+        NxProjectGraph::new(app).ok().map(|graph| Nx { graph })
     }
 
-    pub fn get_nx_app_name(app: &App, env: &Environment) -> Option<String> {
+    pub fn is_nx_monorepo(&self, app: &App, env: &Environment) -> bool {
+        // Only consider an Nx app if an nx app name and project path can be found
+        self.get_nx_project(app, env).is_ok()
+    }
+
+    pub fn get_nx_app_name(&self, app: &App, env: &Environment) -> Option<String> {
+        self.get_nx_app_name_with_source(app, env)
+            .map(|(app_name, _)| app_name)
+    }
+
+    /// Returns the app name along with where it was found
+    pub fn get_nx_app_name_with_source(
+        &self,
+        app: &App,
+        env: &Environment,
+    ) -> Option<(String, NxAppSource)> {
         // First, check if explicitly set via environment variable
         if let Some(app_name) = env.get_config_variable(NX_APP_NAME_ENV_VAR) {
-            return Some(app_name);
+            return Some((app_name, NxAppSource::EnvVar));
+        }
+
+        // When building several apps, the first one is started unless NX_APP_NAME says otherwise
+        if let Some(app_name) = Nx::get_nx_app_names(env).into_iter().next() {
+            return Some((app_name, NxAppSource::EnvVar));
         }
 
         // Second, check nx.json for default project
         if let Ok(nx_json) = app.read_json::<NxJson>("nx.json") {
             if let Some(default_project) = nx_json.default_project {
-                return Some(default_project);
+                return Some((default_project, NxAppSource::DefaultProject));
+            }
+        }
+        if let Some(workspace_json) = Nx::read_workspace_json(app) {
+            if let Some(default_project) = workspace_json.default_project {
+                return Some((default_project, NxAppSource::DefaultProject));
             }
         }
 
-        // Third, try to auto-detect by looking for apps with valid configurations
-        if app.includes_directory("apps") {
-            // Look for directories in apps/ that have either project.json or package.json with nx config
-            if let Ok(app_dirs) = app.find_directories("apps/*") {
-                for app_dir in app_dirs {
-                    if let Some(app_name) = app_dir.file_name().and_then(|n| n.to_str()) {
-                        let app_path = format!("apps/{app_name}");
-                        // Check if this app has a valid project.json
-                        let project_json_path = format!("{app_path}/project.json");
-                        if app.includes_file(&project_json_path) {
-                            return Some(app_name.to_string());
-                        }
-                        // Check if this app has a package.json with nx targets
-                        let package_json_path = format!("{app_path}/package.json");
-                        if app.includes_file(&package_json_path) {
-                            if let Ok(pkg_json) =
-                                app.read_json::<serde_json::Value>(&package_json_path)
-                            {
-                                if pkg_json
-                                    .get("nx")
-                                    .and_then(|nx| nx.get("targets"))
-                                    .is_some()
-                                {
-                                    return Some(app_name.to_string());
-                                }
-                            }
-                        }
-                    }
+        // Third, try to auto-detect from the project graph
+        let (build_target, _) = Nx::get_build_target(env);
+        self.graph
+            .detect_app_name(&build_target)
+            .map(|app_name| (app_name, NxAppSource::AutoDetect))
+    }
+
+    /// Describes the selected app for the plan metadata, e.g. `nx-app:api` and `nx-source:auto-detect`
+    pub fn get_nx_metadata(&self, app: &App, env: &Environment) -> Vec<String> {
+        let mut metadata = Vec::new();
+        if let Some((_, source)) = self.get_nx_app_name_with_source(app, env) {
+            metadata.push(format!("nx-source:{}", source.as_str()));
+        }
+        if let Ok(project) = self.get_nx_project(app, env) {
+            let (build_target_name, _) = Nx::get_build_target(env);
+            metadata.push(format!("nx-app:{}", project.name));
+            metadata.push(format!("nx-root:{}", project.root));
+            if let Some(executor) = project
+                .get_target(&build_target_name)
+                .and_then(|build_target| build_target.executor.as_ref())
+            {
+                metadata.push(format!("nx-executor:{executor}"));
+            }
+            metadata.push(format!(
+                "nx-output:{}",
+                Nx::get_nx_output_path(project, &build_target_name)
+            ));
+        }
+
+        metadata
+    }
+
+    /// Resolves the selected app in the project graph, ensuring it can be built
+    pub fn get_nx_project(&self, app: &App, env: &Environment) -> Result<&NxProject> {
+        let graph = &self.graph;
+        let nx_app_name = self.get_nx_app_name(app, env).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Could not find an NX app to build. Set the {} environment variable to specify the app name explicitly.",
+                NX_APP_NAME_ENV_VAR
+            )
+        })?;
+
+        let (build_target, _) = Nx::get_build_target(env);
+        match graph.get_project(&nx_app_name) {
+            Some(project) if project.get_target(&build_target).is_some() => Ok(project),
+            None => {
+                let suggestion = graph
+                    .get_closest_project_name(&nx_app_name)
+                    .map(|name| format!(" Did you mean '{name}'?"))
+                    .unwrap_or_default();
+                Err(anyhow::anyhow!(
+                    "NX app '{}' does not exist.{} Available projects: {}",
+                    nx_app_name,
+                    suggestion,
+                    graph
+                        .projects
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
+            Some(_) => Err(anyhow::anyhow!(
+                "No '{}' target found for NX app '{}'. For NX 20+, ensure your app's package.json contains an 'nx.targets' section with 'build' and 'start' targets. For older NX versions, ensure your app has a project.json file with target definitions. You can also set the {} environment variable to specify the app name explicitly, or {} to use a different build target.",
+                build_target,
+                nx_app_name,
+                NX_APP_NAME_ENV_VAR,
+                NX_BUILD_TARGET_ENV_VAR
+            )),
+        }
+    }
+
+    /// Fails with the list of projects when NX_APP_NAME doesn't match one, instead of silently
+    /// building the workspace as a plain Node app
+    pub fn validate_nx_app_name(&self, app: &App, env: &Environment) -> Result<()> {
+        if env.get_config_variable(NX_APP_NAME_ENV_VAR).is_some() {
+            self.get_nx_project(app, env)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether nx.json connects the workspace to Nx Cloud for remote caching
+    pub fn uses_nx_cloud(app: &App) -> bool {
+        match app.read_json::<NxJson>("nx.json") {
+            Ok(nx_json) => {
+                nx_json.nx_cloud_access_token.is_some()
+                    || nx_json.nx_cloud_id.is_some()
+                    || Nx::get_nx_cloud_runner(&nx_json).is_some()
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Forwards the build secret `NX_CLOUD_ACCESS_TOKEN` to Nx by reference, so the token itself
+    /// never ends up in the plan
+    fn get_nx_cloud_token_prefix(app: &App, env: &Environment) -> String {
+        if Nx::uses_nx_cloud(app) && env.get_variable(NX_CLOUD_ACCESS_TOKEN_ENV_VAR).is_some() {
+            format!("{NX_CLOUD_ACCESS_TOKEN_ENV_VAR}=\"${NX_CLOUD_ACCESS_TOKEN_ENV_VAR}\" ")
+        } else {
+            String::new()
+        }
+    }
+
+    /// Returns the name of the `tasksRunnerOptions` entry using the Nx Cloud runner
+    fn get_nx_cloud_runner(nx_json: &NxJson) -> Option<String> {
+        nx_json
+            .tasks_runner_options
+            .as_ref()?
+            .iter()
+            .find(|(_, runner)| {
+                runner
+                    .runner
+                    .as_ref()
+                    .is_some_and(|runner| runner.contains("nx-cloud"))
+            })
+            .map(|(name, _)| name.clone())
+    }
+
+    /// Returns the apps listed in `NIXPACKS_NX_APP_NAMES`, e.g. `api,worker`
+    pub fn get_nx_app_names(env: &Environment) -> Vec<String> {
+        env.get_config_variable(NX_APP_NAMES_ENV_VAR)
+            .map(|app_names| {
+                app_names
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|app_name| !app_name.is_empty())
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the target and optional configuration to build, e.g. `build-prod:staging`
+    pub fn get_build_target(env: &Environment) -> (String, Option<String>) {
+        parse_target(
+            env.get_config_variable(NX_BUILD_TARGET_ENV_VAR),
+            DEFAULT_BUILD_TARGET,
+        )
+    }
+
+    /// Returns the target and optional configuration to start, e.g. `serve:production`
+    pub fn get_start_target(env: &Environment) -> (String, Option<String>) {
+        parse_target(
+            env.get_config_variable(NX_START_TARGET_ENV_VAR),
+            DEFAULT_START_TARGET,
+        )
+    }
+
+    /// Returns the apps and libs directories, honoring `workspaceLayout` in nx.json
+    pub fn get_workspace_layout(nx_json: Option<&NxJson>) -> (String, String) {
+        let (apps_dir, libs_dir) =
+            match nx_json.and_then(|nx_json| nx_json.workspace_layout.as_ref()) {
+                Some(layout) => (layout.apps_dir.clone(), layout.libs_dir.clone()),
+                None => (None, None),
+            };
+
+        (
+            apps_dir
+                .map(|dir| dir.trim_matches('/').to_string())
+                .unwrap_or_else(|| DEFAULT_APPS_DIR.to_string()),
+            libs_dir
+                .map(|dir| dir.trim_matches('/').to_string())
+                .unwrap_or_else(|| DEFAULT_LIBS_DIR.to_string()),
+        )
+    }
+
+    /// Merges nx.json `targetDefaults` into the targets, keyed by executor or by target name.
+    /// Values set on the project's own target take precedence.
+    fn apply_target_defaults(
+        targets: &mut BTreeMap<String, Target>,
+        target_defaults: &BTreeMap<String, Target>,
+    ) {
+        for (target_name, target) in targets.iter_mut() {
+            let by_executor = target
+                .executor
+                .as_ref()
+                .and_then(|executor| target_defaults.get(executor));
+
+            let by_name = target_defaults.get(target_name).filter(|target_default| {
+                // Defaults for a target name only apply if the executors are compatible
+                match (&target_default.executor, &target.executor) {
+                    (Some(default_executor), Some(executor)) => default_executor == executor,
+                    _ => true,
                 }
+            });
+
+            if let Some(target_default) = by_executor.or(by_name) {
+                *target = Target::merge(target_default, target);
             }
         }
+    }
 
-        None
+    /// Synthesizes the targets Nx 18+ plugins infer from config files in the project root
+    fn get_inferred_targets(
+        app: &App,
+        root: &str,
+        plugins: &[&NxPlugin],
+    ) -> BTreeMap<String, Target> {
+        let mut targets = BTreeMap::new();
+        for plugin in plugins {
+            let config_files: &[&str] = match plugin.name() {
+                "@nx/vite/plugin" => &["vite.config.ts", "vite.config.mts", "vite.config.js"],
+                "@nx/next/plugin" => &["next.config.js", "next.config.mjs", "next.config.ts"],
+                "@nx/webpack/plugin" => &["webpack.config.js", "webpack.config.ts"],
+                _ => continue,
+            };
+            if !config_files
+                .iter()
+                .any(|config_file| app.includes_file(&format!("{root}/{config_file}")))
+            {
+                continue;
+            }
+
+            let outputs = if plugin.name() == "@nx/next/plugin" {
+                "{workspaceRoot}/{projectRoot}/.next"
+            } else {
+                "{workspaceRoot}/dist/{projectRoot}"
+            };
+            targets.insert(
+                plugin.target_name("buildTargetName", DEFAULT_BUILD_TARGET),
+                Target {
+                    executor: None,
+                    outputs: Some(vec![outputs.to_string()]),
+                    options: None,
+                    configurations: None,
+                },
+            );
+
+            // Next apps are started with the inferred `next start` target
+            if plugin.name() == "@nx/next/plugin" {
+                targets.insert(
+                    plugin.target_name("startTargetName", DEFAULT_START_TARGET),
+                    Target {
+                        executor: None,
+                        outputs: None,
+                        options: None,
+                        configurations: None,
+                    },
+                );
+            }
+        }
+
+        targets
+    }
+
+    fn apply_inferred_targets(
+        targets: &mut BTreeMap<String, Target>,
+        inferred_targets: BTreeMap<String, Target>,
+    ) {
+        for (target_name, inferred_target) in inferred_targets {
+            let target = match targets.get(&target_name) {
+                Some(target) => Target::merge(&inferred_target, target),
+                None => inferred_target,
+            };
+            targets.insert(target_name, target);
+        }
     }
 
-    pub fn get_nx_project_json_for_app(app: &App, nx_app_name: &String) -> Result<ProjectJson> {
+    /// Nx runs package.json scripts as `nx:run-script` targets, limited to `nx.includedScripts` if set
+    fn get_script_targets(pkg_json: &Value) -> BTreeMap<String, Target> {
+        let included_scripts = pkg_json
+            .get("nx")
+            .and_then(|nx| nx.get("includedScripts"))
+            .and_then(Value::as_array);
+        let scripts = match pkg_json.get("scripts").and_then(Value::as_object) {
+            Some(scripts) => scripts,
+            None => return BTreeMap::new(),
+        };
+
+        scripts
+            .keys()
+            .filter(|script| match included_scripts {
+                Some(included_scripts) => included_scripts
+                    .iter()
+                    .any(|included| included.as_str() == Some(script.as_str())),
+                None => true,
+            })
+            .map(|script| {
+                (
+                    script.clone(),
+                    Target {
+                        executor: Some("nx:run-script".to_string()),
+                        outputs: None,
+                        options: None,
+                        configurations: None,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn read_workspace_json(app: &App) -> Option<WorkspaceJson> {
+        ["workspace.json", "angular.json"]
+            .iter()
+            .find_map(|workspace_file| app.read_json::<WorkspaceJson>(workspace_file).ok())
+    }
+
+    fn read_project_json(
+        app: &App,
+        root: &str,
+        config_file: &str,
+        has_inferred_targets: bool,
+    ) -> Option<ProjectJson> {
+        let path = format!("{root}/{config_file}");
+
         // Try project.json (old style NX configuration)
-        let project_path = format!("./apps/{nx_app_name}/project.json");
-        if let Ok(project_json) = app.read_json::<ProjectJson>(&project_path) {
-            return Ok(project_json);
+        if config_file == "project.json" {
+            return app.read_json::<ProjectJson>(&path).ok();
         }
 
         // Try package.json (new NX 20+ style configuration)
-        let package_path = format!("./apps/{nx_app_name}/package.json");
-        if let Ok(pkg_json) = app.read_json::<serde_json::Value>(&package_path) {
-            if let Some(nx) = pkg_json.get("nx") {
-                // If targets exist, use them
-                if let Some(targets) = nx.get("targets") {
-                    let targets: Targets = serde_json::from_value(targets.clone())?;
-                    return Ok(ProjectJson { targets });
-                }
+        let pkg_json = app.read_json::<Value>(&path).ok()?;
+        let script_targets = Nx::get_script_targets(&pkg_json);
+        let mut project_json = match pkg_json.get("nx") {
+            Some(nx) if nx.get("targets").is_some() => {
+                serde_json::from_value::<ProjectJson>(nx.clone()).ok()?
             }
+            _ if has_inferred_targets || !script_targets.is_empty() => ProjectJson {
+                name: None,
+                root: None,
+                project_type: None,
+                targets: BTreeMap::new(),
+            },
+            _ => return None,
+        };
+        Nx::apply_inferred_targets(&mut project_json.targets, script_targets);
+
+        // Nx names package.json projects after the package unless `nx.name` is set
+        if project_json.name.is_none() {
+            project_json.name = pkg_json
+                .get("name")
+                .and_then(Value::as_str)
+                .map(String::from);
         }
 
-        Err(anyhow::anyhow!(
-            "No build/start targets found for NX app '{}'. For NX 20+, ensure your app's package.json contains an 'nx.targets' section with 'build' and 'start' targets. For older NX versions, ensure your app has a project.json file with target definitions. You can also set the {} environment variable to specify the app name explicitly.",
-            nx_app_name,
-            NX_APP_NAME_ENV_VAR
-        ))
+        Some(project_json)
     }
 
-    pub fn get_nx_output_path(app: &App, nx_app_name: &String) -> Result<String> {
-        let project_json = Nx::get_nx_project_json_for_app(app, nx_app_name)?;
-        if let Some(options) = project_json.targets.build.options {
-            if let Some(output_path) = options.output_path {
-                if let Some(the_output_path) = output_path.as_str() {
-                    return Ok(the_output_path.to_string());
+    pub fn get_nx_output_path(project: &NxProject, build_target: &str) -> String {
+        if let Some(build_target) = project.get_target(build_target) {
+            // outputPath is either a string or an object like `{ "base": "dist/apps/api" }`
+            let output_path = build_target
+                .options
+                .as_ref()
+                .and_then(|options| options.output_path.as_ref())
+                .and_then(|output_path| {
+                    output_path
+                        .as_str()
+                        .or_else(|| output_path.get("base").and_then(Value::as_str))
+                })
+                .map(|output_path| interpolate_output(output_path, project, None));
+
+            if let Some(output_path) = &output_path {
+                if !output_path.contains('{') {
+                    return output_path.clone();
+                }
+            }
+
+            // Otherwise use the first declared output, e.g. `{options.outputPath}` or `{workspaceRoot}/dist/{projectRoot}`
+            if let Some(outputs) = &build_target.outputs {
+                for output in outputs {
+                    let output = interpolate_output(output, project, output_path.as_deref());
+                    if !output.contains('{') {
+                        return output;
+                    }
                 }
             }
         }
 
-        Ok(format!("dist/apps/{nx_app_name}"))
+        format!("dist/{}", project.root)
     }
 
-    pub fn get_nx_build_cmd(app: &App, env: &Environment) -> Option<String> {
-        Nx::get_nx_app_name(app, env).map(|nx_app_name| {
-            format!(
-                "{} nx run {nx_app_name}:build:production",
+    pub fn get_nx_build_cmd(&self, app: &App, env: &Environment) -> Option<String> {
+        let project = self.get_nx_project(app, env).ok()?;
+        let (build_target, configuration) = Nx::get_build_target(env);
+        // Build with production unless another configuration is requested or the target has none
+        let configuration = configuration
+            .or_else(|| env.get_config_variable(NX_CONFIGURATION_ENV_VAR))
+            .or_else(|| {
+                has_production_configuration(project.get_target(&build_target))
+                    .then(|| "production".to_string())
+            });
+        let build_cmd = self.get_nx_run_build_cmd(app, env, project, &build_target, configuration);
+
+        // Stop the build when the selected app hasn't changed since the base ref, since there would
+        // be no build output to start
+        if let Some(base) = env.get_config_variable(NX_BASE_ENV_VAR) {
+            let app_name = &project.name;
+            return Some(format!(
+                "[ -d .git ] || {{ echo 'NIXPACKS_NX_BASE needs the .git directory in the build context' >&2; exit 1; }}; if {} nx show projects --affected --base={base} | grep -qx '{app_name}'; then {build_cmd}; else echo '{app_name} is not affected since {base}, stopping the build' >&2; exit 1; fi",
                 NodeProvider::get_package_manager_dlx_command(app)
-            )
-        })
+            ));
+        }
+
+        Some(build_cmd)
     }
 
-    pub fn get_nx_start_cmd(app: &App, env: &Environment) -> Result<Option<String>> {
-        if !Nx::is_nx_monorepo(app, env) {
+    fn get_nx_run_build_cmd(
+        &self,
+        app: &App,
+        env: &Environment,
+        project: &NxProject,
+        build_target: &str,
+        configuration: Option<String>,
+    ) -> String {
+        let configuration_flag = configuration
+            .as_ref()
+            .map(|configuration| format!(" --configuration={configuration}"))
+            .unwrap_or_default();
+
+        // A cloud runner that isn't the default one has to be selected explicitly
+        let runner = match app.read_json::<NxJson>("nx.json") {
+            Ok(nx_json) => Nx::get_nx_cloud_runner(&nx_json)
+                .filter(|runner| runner != "default")
+                .map(|runner| format!(" --runner={runner}"))
+                .unwrap_or_default(),
+            Err(_) => String::new(),
+        };
+
+        let mut app_names = Nx::get_nx_app_names(env)
+            .into_iter()
+            .map(|app_name| match self.graph.get_project(&app_name) {
+                Some(project) => project.name.clone(),
+                None => app_name,
+            })
+            .collect::<Vec<_>>();
+        if !app_names.is_empty() {
+            // The started app always has to be built
+            if !app_names.contains(&project.name) {
+                app_names.push(project.name.clone());
+            }
+
+            return format!(
+                "{}{} nx run-many --targets={build_target} --projects={}{configuration_flag}{runner}",
+                Nx::get_nx_cloud_token_prefix(app, env),
+                NodeProvider::get_package_manager_dlx_command(app),
+                app_names.join(",")
+            );
+        }
+
+        format!(
+            "{}{} nx run {}:{build_target}{}{runner}",
+            Nx::get_nx_cloud_token_prefix(app, env),
+            NodeProvider::get_package_manager_dlx_command(app),
+            project.name,
+            configuration
+                .map(|configuration| format!(":{configuration}"))
+                .unwrap_or_default()
+        )
+    }
+
+    /// Runs the app's `test` target before building when `NIXPACKS_NX_RUN_TESTS` is set
+    pub fn get_nx_test_cmd(&self, app: &App, env: &Environment) -> Option<String> {
+        if !env.is_config_variable_truthy(NX_RUN_TESTS_ENV_VAR) {
+            return None;
+        }
+
+        let project = self.get_nx_project(app, env).ok()?;
+        project.get_target(DEFAULT_TEST_TARGET)?;
+
+        Some(format!(
+            "{} nx run {}:{DEFAULT_TEST_TARGET}",
+            NodeProvider::get_package_manager_dlx_command(app),
+            project.name
+        ))
+    }
+
+    /// Installs only the selected app's dependencies (and the workspace root, which provides nx).
+    /// Only pnpm can do this from the lockfile, and only for apps with their own package.json.
+    pub fn get_nx_prune_install_cmd(&self, app: &App, env: &Environment) -> Option<String> {
+        if !env.is_config_variable_truthy(NX_PRUNE_ENV_VAR)
+            || NodeProvider::get_package_manager(app) != "pnpm"
+        {
+            return None;
+        }
+
+        let project = self.get_nx_project(app, env).ok()?;
+        let package_name = |path: &str| {
+            app.read_json::<Value>(path)
+                .ok()?
+                .get("name")?
+                .as_str()
+                .map(String::from)
+        };
+        let app_package = package_name(&format!("{}/package.json", project.root))?;
+
+        // `.` selects the workspace root, whether or not its package.json has a name
+        Some(format!(
+            "pnpm i --frozen-lockfile --filter {app_package}... --filter ."
+        ))
+    }
+
+    /// Commands to run after the build, e.g. to lay out a Next.js standalone server
+    pub fn get_nx_post_build_cmds(&self, app: &App, env: &Environment) -> Vec<String> {
+        let mut cmds = Vec::new();
+        let project = match self.get_nx_project(app, env) {
+            Ok(project) => project,
+            Err(_) => return cmds,
+        };
+        let (build_target_name, _) = Nx::get_build_target(env);
+
+        if let Some(build_target) = project.get_target(&build_target_name) {
+            if is_next_build(build_target) && Next::is_standalone(app, &project.root) {
+                let public_dir = format!("{}/public", project.root);
+                cmds.extend(Next::get_standalone_copy_cmds(
+                    &Nx::get_nx_output_path(project, &build_target_name),
+                    app.includes_directory(&public_dir)
+                        .then_some(public_dir.as_str()),
+                ));
+            }
+
+            // The generated package.json and lockfile only list what the app imports
+            if generates_package_json(build_target) {
+                cmds.extend(NpmRegistry::get_auth_setup_cmd(app, env));
+                cmds.push(format!(
+                    "cd {} && {}",
+                    Nx::get_nx_output_path(project, &build_target_name),
+                    NodeProvider::get_prod_install_command(app)
+                ));
+                cmds.extend(NpmRegistry::get_auth_cleanup_cmd(app, env));
+            }
+        }
+
+        cmds
+    }
+
+    /// Returns the output directory of an app that only produces static files, e.g. an Angular app without SSR
+    pub fn get_nx_static_output_dir(&self, app: &App, env: &Environment) -> Option<String> {
+        let project = self.get_nx_project(app, env).ok()?;
+        let (build_target_name, _) = Nx::get_build_target(env);
+        let build_target = project.get_target(&build_target_name)?;
+        let executor = build_target.executor.as_deref()?;
+
+        if ANGULAR_BROWSER_EXECUTORS.contains(&executor) {
+            return Some(Nx::get_nx_output_path(project, &build_target_name));
+        }
+
+        if ANGULAR_APPLICATION_EXECUTORS.contains(&executor) && !is_angular_ssr(build_target) {
+            return Some(get_angular_output_dir(
+                project,
+                &build_target_name,
+                "browser",
+            ));
+        }
+
+        if VITE_EXECUTORS.contains(&executor) && get_vite_ssr_entry(build_target).is_none() {
+            return Some(Nx::get_nx_output_path(project, &build_target_name));
+        }
+
+        None
+    }
+
+    pub fn get_nx_start_cmd(&self, app: &App, env: &Environment) -> Result<Option<String>> {
+        if !self.is_nx_monorepo(app, env) {
             return Ok(None);
         }
 
-        if let Some(nx_app_name) = Nx::get_nx_app_name(app, env) {
-            let output_path = Nx::get_nx_output_path(app, &nx_app_name)?;
-            let project_json = Nx::get_nx_project_json_for_app(app, &nx_app_name)?;
+        let project = self.get_nx_project(app, env)?;
+        let nx_app_name = &project.name;
+        let (build_target_name, _) = Nx::get_build_target(env);
+        let (start_target_name, start_configuration) = Nx::get_start_target(env);
+        let output_path = Nx::get_nx_output_path(project, &build_target_name);
+        // Bun workspaces run the build output with bun
+        let runtime = NodeProvider::get_executor(app);
 
-            if let Some(start_target) = project_json.targets.start {
-                if let Some(configurations) = start_target.configurations {
-                    if configurations.production.is_some() {
-                        return Ok(Some(format!(
-                            "{} nx run {nx_app_name}:start:production",
-                            NodeProvider::get_package_manager_dlx_command(app)
-                        )));
-                    }
-                }
-                return Ok(Some(format!(
-                    "{} nx run {nx_app_name}:start",
+        let start_target = project.get_target(&start_target_name);
+        // A start target set explicitly is used even if it is not declared in the project (e.g. inferred by a plugin)
+        if start_target.is_some() || env.get_config_variable(NX_START_TARGET_ENV_VAR).is_some() {
+            let configuration = start_configuration.or_else(|| {
+                has_production_configuration(start_target).then(|| "production".to_string())
+            });
+
+            return Ok(Some(match configuration {
+                Some(configuration) => format!(
+                    "{} nx run {nx_app_name}:{start_target_name}:{configuration}",
+                    NodeProvider::get_package_manager_dlx_command(app)
+                ),
+                None => format!(
+                    "{} nx run {nx_app_name}:{start_target_name}",
                     NodeProvider::get_package_manager_dlx_command(app)
+                ),
+            }));
+        }
+
+        if let Some(build_target) = project.get_target(&build_target_name) {
+            // Angular SSR builds emit a server entrypoint next to the browser bundle
+            if build_target
+                .executor
+                .as_deref()
+                .is_some_and(|executor| ANGULAR_APPLICATION_EXECUTORS.contains(&executor))
+                && is_angular_ssr(build_target)
+            {
+                return Ok(Some(format!(
+                    "{runtime} {}/server.mjs",
+                    get_angular_output_dir(project, &build_target_name, "server")
                 )));
             }
 
-            if project_json.targets.build.executor == "@nx/next:build"
-                || project_json.targets.build.executor == "@nrwl/next:build"
-            {
-                return Ok(Some(format!("cd {output_path} && npm run start")));
+            if let Some(executor) = build_target.executor.as_deref() {
+                if REMIX_EXECUTORS.contains(&executor) {
+                    return Ok(Some(format!(
+                        "{} remix-serve {output_path}/build/index.js",
+                        NodeProvider::get_package_manager_dlx_command(app)
+                    )));
+                }
+
+                // Nitro writes the Nuxt server to .output inside the output path
+                if NUXT_EXECUTORS.contains(&executor) {
+                    return Ok(Some(format!(
+                        "{runtime} {output_path}/.output/server/index.mjs"
+                    )));
+                }
+
+                if VITE_EXECUTORS.contains(&executor) {
+                    if let Some(ssr_entry) = get_vite_ssr_entry(build_target) {
+                        let ssr_entry = PathBuf::from(ssr_entry);
+                        let file_name = ssr_entry.file_stem().unwrap().to_str().unwrap();
+
+                        return Ok(Some(format!("{runtime} {output_path}/{file_name}.js")));
+                    }
+                }
             }
 
-            if let Some(options) = project_json.targets.build.options {
-                if let Some(main_path) = options.main {
-                    let current_path = PathBuf::from(main_path);
-                    let file_name = current_path.file_stem().unwrap().to_str().unwrap();
+            // Static Angular and Vite output is served by Caddy instead
+            if self.get_nx_static_output_dir(app, env).is_some() {
+                return Ok(None);
+            }
+
+            if is_next_build(build_target) {
+                if Next::is_standalone(app, &project.root) {
+                    return Ok(Some(Next::get_standalone_start_cmd(&output_path)));
+                }
+
+                let package_manager = if runtime == "bun" { "bun" } else { "npm" };
+                return Ok(Some(format!(
+                    "cd {output_path} && {package_manager} run start"
+                )));
+            }
 
-                    return Ok(Some(format!("node {output_path}/{file_name}.js")));
+            if let Some(entry_file) = get_entry_file(project, build_target) {
+                if generates_package_json(build_target) {
+                    return Ok(Some(format!("cd {output_path} && {runtime} {entry_file}")));
                 }
+
+                return Ok(Some(format!("{runtime} {output_path}/{entry_file}")));
             }
-            return Ok(Some(format!("node {output_path}/index.js")));
         }
 
-        Ok(None)
+        Ok(Some(format!("{runtime} {output_path}/index.js")))
+    }
+}
+
+fn parse_target(value: Option<String>, default_target: &str) -> (String, Option<String>) {
+    match value {
+        Some(value) => match value.split_once(':') {
+            Some((target, configuration)) => (target.to_string(), Some(configuration.to_string())),
+            None => (value, None),
+        },
+        None => (default_target.to_string(), None),
+    }
+}
+
+/// The file the build emits for `options.main`, relative to the output path
+fn get_entry_file(project: &NxProject, build_target: &Target) -> Option<String> {
+    let options = build_target.options.as_ref()?;
+    if let Some(output_file_name) = &options.output_file_name {
+        return Some(output_file_name.clone());
+    }
+
+    let main_path = PathBuf::from(options.main.as_ref()?);
+    let is_esbuild = matches!(
+        build_target.executor.as_deref(),
+        Some("@nx/esbuild:esbuild" | "@nrwl/esbuild:esbuild")
+    );
+    if !is_esbuild {
+        let file_name = main_path.file_stem()?.to_str()?;
+        return Some(format!("{file_name}.js"));
+    }
+
+    // esbuild writes .js for ESM and .cjs for CommonJS, unless outExtension overrides it
+    let formats = match &options.format {
+        Some(Value::String(format)) => vec![format.as_str()],
+        Some(Value::Array(formats)) => formats.iter().filter_map(Value::as_str).collect(),
+        _ => vec!["esm"],
+    };
+    let extension = options
+        .esbuild_options
+        .as_ref()
+        .and_then(|esbuild_options| esbuild_options.get("outExtension"))
+        .and_then(|out_extension| out_extension.get(".js"))
+        .and_then(Value::as_str)
+        .unwrap_or(if formats.contains(&"esm") {
+            ".js"
+        } else {
+            ".cjs"
+        });
+
+    // Without bundling, esbuild keeps the source layout relative to the project root
+    let main_path = if options.bundle == Some(false) {
+        main_path
+            .strip_prefix(&project.root)
+            .unwrap_or(&main_path)
+            .with_extension("")
+    } else {
+        PathBuf::from(main_path.file_stem()?)
+    };
+
+    Some(format!("{}{extension}", main_path.to_slash()?))
+}
+
+fn has_production_configuration(target: Option<&Target>) -> bool {
+    target
+        .and_then(|target| target.configurations.as_ref())
+        .is_some_and(|configurations| configurations.production.is_some())
+}
+
+fn generates_package_json(build_target: &Target) -> bool {
+    build_target
+        .options
+        .as_ref()
+        .is_some_and(|options| options.generate_package_json == Some(true))
+}
+
+fn is_next_build(build_target: &Target) -> bool {
+    matches!(
+        build_target.executor.as_deref(),
+        Some("@nx/next:build" | "@nrwl/next:build")
+    )
+}
+
+fn is_angular_ssr(build_target: &Target) -> bool {
+    build_target.options.as_ref().is_some_and(|options| {
+        options.server.is_some()
+            || options
+                .ssr
+                .as_ref()
+                .is_some_and(|ssr| ssr.is_object() || ssr.as_bool() == Some(true))
+    })
+}
+
+/// The entry of a Vite SSR build, e.g. `"ssr": "apps/web/src/entry-server.ts"`
+fn get_vite_ssr_entry(build_target: &Target) -> Option<String> {
+    build_target
+        .options
+        .as_ref()
+        .and_then(|options| options.ssr.as_ref())
+        .and_then(Value::as_str)
+        .map(String::from)
+}
+
+/// The Angular application builder writes to `<outputPath>/browser` and `<outputPath>/server`,
+/// unless the object form of outputPath renames those folders
+fn get_angular_output_dir(project: &NxProject, build_target: &str, kind: &str) -> String {
+    let output_path = Nx::get_nx_output_path(project, build_target);
+    let folder = project
+        .get_target(build_target)
+        .and_then(|target| target.options.as_ref())
+        .and_then(|options| options.output_path.as_ref())
+        .and_then(|output_path| output_path.get(kind))
+        .and_then(Value::as_str)
+        .unwrap_or(kind);
+
+    if folder.is_empty() {
+        output_path
+    } else {
+        format!("{output_path}/{folder}")
+    }
+}
+
+/// Resolves the `{workspaceRoot}`, `{projectRoot}`, `{projectName}` and `{options.outputPath}` tokens Nx allows in outputs
+fn interpolate_output(output: &str, project: &NxProject, output_path: Option<&str>) -> String {
+    let mut output = output
+        .replace("{workspaceRoot}/", "")
+        .replace("{workspaceRoot}", ".")
+        .replace("{projectRoot}", &project.root)
+        .replace("{projectName}", &project.name);
+    if let Some(output_path) = output_path {
+        output = output.replace("{options.outputPath}", output_path);
+    }
+
+    output
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut distances = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous = distances[0];
+        distances[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a_char != *b_char);
+            previous = distances[j + 1];
+            distances[j + 1] = substitution.min(previous + 1).min(distances[j] + 1);
+        }
+    }
+
+    distances[b.len()]
+}
+
+fn is_inside_dir(path: &str, dir: &str) -> bool {
+    path.strip_prefix(dir)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_project_graph() {
+        let graph = NxProjectGraph::new(&App::new("examples/node-nx").unwrap()).unwrap();
+        assert_eq!(graph.projects.len(), 6);
+
+        let project = graph.get_project("express-app").unwrap();
+        assert_eq!(project.root, "apps/express-app");
+        assert_eq!(
+            project.targets.keys().collect::<Vec<_>>(),
+            vec!["build", "lint", "serve", "test"]
+        );
+    }
+
+    #[test]
+    fn test_workspace_layout() {
+        let app = App::new("examples/node-nx-workspace-layout").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let project = nx.get_nx_project(&app, &Environment::default()).unwrap();
+
+        assert_eq!(project.name, "api");
+        assert_eq!(project.root, "packages/api");
+        assert_eq!(
+            Nx::get_nx_output_path(project, "build"),
+            "dist/packages/api"
+        );
+    }
+
+    #[test]
+    fn test_target_defaults() {
+        let app = App::new("examples/node-nx-target-defaults").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+        let build_target = graph
+            .get_project("api")
+            .unwrap()
+            .get_target("build")
+            .unwrap();
+
+        assert_eq!(
+            build_target.executor.as_deref(),
+            Some("@nx/webpack:webpack")
+        );
+        assert!(build_target
+            .configurations
+            .as_ref()
+            .is_some_and(|configurations| configurations.production.is_some()));
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &Environment::default()).unwrap(),
+            Some("node dist/apps/api/server.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_custom_targets() {
+        let app = App::new("examples/node-nx").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env = Environment::new(BTreeMap::from([
+            (
+                "NIXPACKS_NX_APP_NAME".to_string(),
+                "express-app".to_string(),
+            ),
+            (
+                "NIXPACKS_NX_BUILD_TARGET".to_string(),
+                "build:development".to_string(),
+            ),
+            ("NIXPACKS_NX_START_TARGET".to_string(), "serve".to_string()),
+        ]));
+
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run express-app:build:development".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("npx nx run express-app:serve:production".to_string())
+        );
+    }
+
+    #[test]
+    fn test_multiple_apps() {
+        let app = App::new("examples/node-nx").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_APP_NAMES".to_string(),
+            "express-app, start-command".to_string(),
+        )]));
+
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run-many --targets=build --projects=express-app,start-command --configuration=production".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("node dist/apps/express-app/main.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_affected() {
+        let app = App::new("examples/node-nx").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_BASE".to_string(),
+            "origin/main".to_string(),
+        )]));
+
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some(
+                "[ -d .git ] || { echo 'NIXPACKS_NX_BASE needs the .git directory in the build context' >&2; exit 1; }; if npx nx show projects --affected --base=origin/main | grep -qx 'express-app'; then npx nx run express-app:build:production; else echo 'express-app is not affected since origin/main, stopping the build' >&2; exit 1; fi"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_nx_cloud_token() {
+        let app = App::new("examples/node-nx-cloud").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env = Environment::new(BTreeMap::from([(
+            "NX_CLOUD_ACCESS_TOKEN".to_string(),
+            "secret".to_string(),
+        )]));
+
+        let build_cmd = nx.get_nx_build_cmd(&app, &env).unwrap();
+        assert_eq!(
+            build_cmd,
+            "NX_CLOUD_ACCESS_TOKEN=\"$NX_CLOUD_ACCESS_TOKEN\" npx nx run api:build --runner=cloud"
+        );
+        assert!(!build_cmd.contains("secret"));
+    }
+
+    #[test]
+    fn test_nx_cloud() {
+        let app = App::new("examples/node-nx-cloud").unwrap();
+        let nx = Nx::new(&app).unwrap();
+
+        assert!(Nx::uses_nx_cloud(&app));
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &Environment::default()),
+            Some("npx nx run api:build --runner=cloud".to_string())
+        );
+
+        assert!(!Nx::uses_nx_cloud(&App::new("examples/node-nx").unwrap()));
+    }
+
+    #[test]
+    fn test_output_path() {
+        let app = App::new("examples/node-nx-output-path").unwrap();
+        let env = Environment::new(BTreeMap::new());
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+
+        assert_eq!(
+            Nx::get_nx_output_path(graph.get_project("api").unwrap(), "build"),
+            "dist/apps/api"
+        );
+        assert_eq!(
+            Nx::get_nx_output_path(graph.get_project("worker").unwrap(), "build"),
+            "build/apps/worker"
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("node dist/apps/api/main.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_inferred_targets() {
+        let app = App::new("examples/node-nx-inferred-targets").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+
+        let site = graph.get_project("site").unwrap();
+        assert!(site.get_target("build").is_some());
+        assert_eq!(Nx::get_nx_output_path(site, "build"), "dist/apps/site");
+
+        let web = graph.get_project("web").unwrap();
+        assert!(web.get_target("build").is_some());
+        assert!(web.get_target("next-start").is_some());
+
+        let env = Environment::new(BTreeMap::from([
+            ("NIXPACKS_NX_APP_NAME".to_string(), "web".to_string()),
+            (
+                "NIXPACKS_NX_START_TARGET".to_string(),
+                "next-start".to_string(),
+            ),
+        ]));
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run web:build".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("npx nx run web:next-start".to_string())
+        );
+    }
+
+    #[test]
+    fn test_nested_project_roots() {
+        let app = App::new("examples/node-nx-nested").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+
+        assert_eq!(graph.get_project("api").unwrap().root, "apps/backend/api");
+        assert_eq!(graph.get_project("backend/api").unwrap().name, "api");
+        assert_eq!(graph.get_project("apps/frontend/web").unwrap().name, "web");
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_APP_NAME".to_string(),
+            "frontend/web".to_string(),
+        )]));
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run web:build".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("node dist/apps/frontend/web/main.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_project_name() {
+        let app = App::new("examples/node-nx-project-name").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+
+        let api = graph.get_project("api").unwrap();
+        assert_eq!(api.root, "apps/api-service");
+        assert!(graph.get_project("api-service").is_some());
+
+        let env = Environment::new(BTreeMap::new());
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run api:build".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("node dist/apps/api-service/main.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_angular() {
+        let app = App::new("examples/node-nx-angular").unwrap();
+        let nx = Nx::new(&app).unwrap();
+
+        let dashboard = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_APP_NAME".to_string(),
+            "dashboard".to_string(),
+        )]));
+        assert_eq!(
+            nx.get_nx_static_output_dir(&app, &dashboard),
+            Some("dist/apps/dashboard/browser".to_string())
+        );
+        assert_eq!(nx.get_nx_start_cmd(&app, &dashboard).unwrap(), None);
+
+        let store = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_APP_NAME".to_string(),
+            "store".to_string(),
+        )]));
+        assert_eq!(nx.get_nx_static_output_dir(&app, &store), None);
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &store).unwrap(),
+            Some("node dist/apps/store/server/server.mjs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_framework_start_commands() {
+        let app = App::new("examples/node-nx-frameworks").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env_for = |app_name: &str| {
+            Environment::new(BTreeMap::from([(
+                "NIXPACKS_NX_APP_NAME".to_string(),
+                app_name.to_string(),
+            )]))
+        };
+
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("remix")).unwrap(),
+            Some("npx remix-serve dist/apps/remix/build/index.js".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("nuxt")).unwrap(),
+            Some("node dist/apps/nuxt/.output/server/index.mjs".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("vite-ssr")).unwrap(),
+            Some("node dist/apps/vite-ssr/entry-server.js".to_string())
+        );
+
+        let vite_spa = env_for("vite-spa");
+        assert_eq!(nx.get_nx_start_cmd(&app, &vite_spa).unwrap(), None);
+        assert_eq!(
+            nx.get_nx_static_output_dir(&app, &vite_spa),
+            Some("dist/apps/vite-spa".to_string())
+        );
+    }
+
+    #[test]
+    fn test_next_standalone() {
+        let app = App::new("examples/node-nx-next-standalone").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env = Environment::new(BTreeMap::new());
+
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("node dist/apps/web/.next/standalone/server.js".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_post_build_cmds(&app, &env),
+            vec![
+                "cp -r dist/apps/web/.next/static dist/apps/web/.next/standalone/.next/static"
+                    .to_string(),
+                "cp -r apps/web/public dist/apps/web/.next/standalone/public".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entry_files() {
+        let app = App::new("examples/node-nx-entry-files").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env_for = |app_name: &str| {
+            Environment::new(BTreeMap::from([(
+                "NIXPACKS_NX_APP_NAME".to_string(),
+                app_name.to_string(),
+            )]))
+        };
+
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("esm")).unwrap(),
+            Some("node dist/apps/esm/main.js".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("cjs")).unwrap(),
+            Some("node dist/apps/cjs/main.cjs".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("mjs")).unwrap(),
+            Some("node dist/apps/mjs/server.mjs".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("unbundled")).unwrap(),
+            Some("node dist/apps/unbundled/src/main.js".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env_for("webpack")).unwrap(),
+            Some("node dist/apps/webpack/server.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prune_install() {
+        let app = App::new("examples/node-nx-pnpm-prune").unwrap();
+        let nx = Nx::new(&app).unwrap();
+
+        assert_eq!(
+            nx.get_nx_prune_install_cmd(&app, &Environment::new(BTreeMap::new())),
+            None
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_PRUNE".to_string(),
+            "true".to_string(),
+        )]));
+        assert_eq!(
+            nx.get_nx_prune_install_cmd(&app, &env),
+            Some(
+                "pnpm i --frozen-lockfile --filter @node-nx-pnpm-prune/api... --filter ."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_build_configuration() {
+        let app = App::new("examples/node-nx-target-defaults").unwrap();
+        let nx = Nx::new(&app).unwrap();
+
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &Environment::new(BTreeMap::new())),
+            Some("npx nx run api:build:production".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_CONFIGURATION".to_string(),
+            "staging".to_string(),
+        )]));
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run api:build:staging".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unknown_app_name() {
+        let app = App::new("examples/node-nx-frameworks").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_APP_NAME".to_string(),
+            "remx".to_string(),
+        )]));
+
+        let error = nx.get_nx_project(&app, &env).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "NX app 'remx' does not exist. Did you mean 'remix'? Available projects: nuxt, remix, vite-spa, vite-ssr"
+        );
+        assert_eq!(graph.get_closest_project_name("something-else"), None);
+        assert!(nx.validate_nx_app_name(&app, &env).is_err());
+    }
+
+    #[test]
+    fn test_workspace_json() {
+        let app = App::new("examples/node-nx-workspace-json").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+
+        assert_eq!(
+            graph.projects.keys().collect::<Vec<_>>(),
+            vec!["admin", "api", "shared-ui"]
+        );
+        assert_eq!(graph.get_project("api").unwrap().root, "apps/api-server");
+        assert_eq!(
+            graph
+                .get_project("admin")
+                .unwrap()
+                .get_target("build")
+                .unwrap()
+                .executor,
+            Some("@angular-devkit/build-angular:browser".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::new());
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run api:build:production".to_string())
+        );
+    }
+
+    #[test]
+    fn test_package_scripts() {
+        let app = App::new("examples/node-nx-package-scripts").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let graph = &nx.graph;
+
+        let api = graph.get_project("api").unwrap();
+        assert_eq!(
+            api.targets.keys().collect::<Vec<_>>(),
+            vec!["build", "start"]
+        );
+        let web = graph.get_project("web").unwrap();
+        assert_eq!(
+            web.get_target("build").unwrap().executor,
+            Some("nx:run-commands".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_APP_NAME".to_string(),
+            "api".to_string(),
+        )]));
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("npx nx run api:build".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("npx nx run api:start".to_string())
+        );
+    }
+
+    #[test]
+    fn test_generate_package_json() {
+        let app = App::new("examples/node-nx-generate-package-json").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env = Environment::new(BTreeMap::new());
+
+        assert_eq!(
+            nx.get_nx_post_build_cmds(&app, &env),
+            vec!["cd dist/apps/api && npm ci --omit=dev".to_string()]
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("cd dist/apps/api && node main.js".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NPM_TOKEN".to_string(),
+            "secret".to_string(),
+        )]));
+        assert_eq!(
+            nx.get_nx_post_build_cmds(&app, &env),
+            vec![
+                "printf '%s\\n' '//registry.npmjs.org/:_authToken=${NPM_TOKEN}' > /root/.npmrc"
+                    .to_string(),
+                "cd dist/apps/api && npm ci --omit=dev".to_string(),
+                "rm -f /root/.npmrc".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metadata() {
+        let app = App::new("examples/node-nx-target-defaults").unwrap();
+        let nx = Nx::new(&app).unwrap();
+
+        assert_eq!(
+            nx.get_nx_metadata(&app, &Environment::new(BTreeMap::new())),
+            vec![
+                "nx-source:auto-detect",
+                "nx-app:api",
+                "nx-root:apps/api",
+                "nx-executor:@nx/webpack:webpack",
+                "nx-output:dist/apps/api"
+            ]
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_APP_NAME".to_string(),
+            "api".to_string(),
+        )]));
+        assert_eq!(nx.get_nx_metadata(&app, &env)[0], "nx-source:env");
+    }
+
+    #[test]
+    fn test_bun_workspace() {
+        let app = App::new("examples/node-nx-bun").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        let env = Environment::new(BTreeMap::new());
+
+        assert_eq!(
+            nx.get_nx_build_cmd(&app, &env),
+            Some("bunx nx run api:build:production".to_string())
+        );
+        assert_eq!(
+            nx.get_nx_start_cmd(&app, &env).unwrap(),
+            Some("bun dist/apps/api/main.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_tests() {
+        let app = App::new("examples/node-nx").unwrap();
+        let nx = Nx::new(&app).unwrap();
+        assert_eq!(
+            nx.get_nx_test_cmd(&app, &Environment::new(BTreeMap::new())),
+            None
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_NX_RUN_TESTS".to_string(),
+            "1".to_string(),
+        )]));
+        assert_eq!(
+            nx.get_nx_test_cmd(&app, &env),
+            Some("npx nx run express-app:test".to_string())
+        );
     }
 }
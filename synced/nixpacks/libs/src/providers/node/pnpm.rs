@@ -0,0 +1,81 @@
+// Code relating to pnpm workspaces that aren't managed by Nx or Turborepo
+
+use crate::nixpacks::{app::App, environment::Environment};
+
+use super::NodeProvider;
+
+const PNPM_FILTER_ENV_VAR: &str = "PNPM_FILTER";
+
+pub struct PnpmWorkspace;
+
+impl PnpmWorkspace {
+    /// The package selected with `PNPM_FILTER`, if this is a plain pnpm workspace
+    pub fn get_filter(app: &App, env: &Environment) -> Option<String> {
+        if !app.includes_file("pnpm-workspace.yaml")
+            || app.includes_file("nx.json")
+            || app.includes_file("turbo.json")
+        {
+            return None;
+        }
+
+        env.get_config_variable(PNPM_FILTER_ENV_VAR)
+    }
+
+    /// Installs the selected package and the workspace packages it depends on, along with the
+    /// workspace root whose devDependencies (e.g. typescript) are needed to build
+    pub fn get_install_cmd(app: &App, env: &Environment) -> Option<String> {
+        let filter = PnpmWorkspace::get_filter(app, env)?;
+
+        Some(format!(
+            "pnpm i --frozen-lockfile --filter {filter}... --filter ."
+        ))
+    }
+
+    pub fn get_build_cmd(app: &App, env: &Environment) -> Option<String> {
+        let filter = PnpmWorkspace::get_filter(app, env)?;
+
+        Some(NodeProvider::get_workspace_script_cmd(
+            app, &filter, "build",
+        ))
+    }
+
+    pub fn get_start_cmd(app: &App, env: &Environment) -> Option<String> {
+        let filter = PnpmWorkspace::get_filter(app, env)?;
+
+        Some(NodeProvider::get_workspace_script_cmd(
+            app, &filter, "start",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_pnpm_filter() {
+        let app = App::new("examples/node-pnpm-monorepo").unwrap();
+        assert_eq!(
+            PnpmWorkspace::get_install_cmd(&app, &Environment::default()),
+            None
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_PNPM_FILTER".to_string(),
+            "web".to_string(),
+        )]));
+        assert_eq!(
+            PnpmWorkspace::get_install_cmd(&app, &env),
+            Some("pnpm i --frozen-lockfile --filter web... --filter .".to_string())
+        );
+        assert_eq!(
+            PnpmWorkspace::get_build_cmd(&app, &env),
+            Some("pnpm --filter web run build".to_string())
+        );
+        assert_eq!(
+            PnpmWorkspace::get_start_cmd(&app, &env),
+            Some("pnpm --filter web run start".to_string())
+        );
+    }
+}
@@ -0,0 +1,111 @@
+// Code relating to apps using Prisma
+
+use anyhow::{bail, Result};
+
+use crate::nixpacks::{app::App, environment::Environment};
+
+use super::{nx::Nx, NodeProvider, PackageJson, NODE_PRUNE_ENV_VAR};
+
+const PRISMA_MIGRATE_ENV_VAR: &str = "PRISMA_MIGRATE";
+const DEFAULT_SCHEMA_PATH: &str = "prisma/schema.prisma";
+
+pub struct Prisma;
+
+impl Prisma {
+    pub fn is_prisma(app: &App) -> bool {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        app.includes_file(DEFAULT_SCHEMA_PATH) || package_json.has_dependency("@prisma/client")
+    }
+
+    /// The client has to be generated from the schema before it can be imported by the build.
+    /// The installed CLI is used, so it matches the version of `@prisma/client`.
+    pub fn get_generate_cmd(app: &App) -> Option<String> {
+        if !Prisma::is_prisma(app) {
+            return None;
+        }
+
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        if !package_json.has_dependency("prisma") {
+            eprintln!("Warning: `prisma generate` needs the `prisma` package, add it to your devDependencies");
+        }
+
+        Some(format!(
+            "{} prisma generate",
+            NodeProvider::get_package_manager_exec_command(app)
+        ))
+    }
+
+    /// Runs pending migrations before the app starts when `PRISMA_MIGRATE=deploy`. The CLI has
+    /// to be in the final image, which isn't the case after pruning devDependencies or when
+    /// starting a Next.js standalone build.
+    pub fn get_migrate_cmd(
+        app: &App,
+        env: &Environment,
+        nx: Option<&Nx>,
+    ) -> Result<Option<String>> {
+        if !Prisma::is_prisma(app)
+            || env.get_config_variable(PRISMA_MIGRATE_ENV_VAR).as_deref() != Some("deploy")
+        {
+            return Ok(None);
+        }
+
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        let is_runtime_dependency = package_json
+            .dependencies
+            .is_some_and(|deps| deps.contains_key("prisma"));
+        if env.is_config_variable_truthy(NODE_PRUNE_ENV_VAR) && !is_runtime_dependency {
+            bail!(
+                "NIXPACKS_{PRISMA_MIGRATE_ENV_VAR}=deploy needs the `prisma` CLI at runtime, but NIXPACKS_{NODE_PRUNE_ENV_VAR} removes devDependencies. Move `prisma` to dependencies or run the migrations outside the container."
+            );
+        }
+        if NodeProvider::uses_next_standalone_start(app, env, nx) {
+            bail!(
+                "NIXPACKS_{PRISMA_MIGRATE_ENV_VAR}=deploy can't be used with a Next.js standalone build, which only includes `.next/standalone` in the final image. Run the migrations outside the container."
+            );
+        }
+
+        Ok(Some(format!(
+            "{} prisma migrate deploy",
+            NodeProvider::get_package_manager_exec_command(app)
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_prisma() {
+        let app = App::new("examples/node-prisma").unwrap();
+        assert!(Prisma::is_prisma(&app));
+        assert_eq!(
+            Prisma::get_generate_cmd(&app),
+            Some("npx --no-install prisma generate".to_string())
+        );
+        assert_eq!(
+            Prisma::get_migrate_cmd(&app, &Environment::default(), None).unwrap(),
+            None
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_PRISMA_MIGRATE".to_string(),
+            "deploy".to_string(),
+        )]));
+        assert_eq!(
+            Prisma::get_migrate_cmd(&app, &env, None).unwrap(),
+            Some("npx --no-install prisma migrate deploy".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prisma_migrate_pruned() {
+        let app = App::new("examples/node-prisma").unwrap();
+        let env = Environment::new(BTreeMap::from([
+            ("NIXPACKS_PRISMA_MIGRATE".to_string(), "deploy".to_string()),
+            ("NIXPACKS_NODE_PRUNE".to_string(), "true".to_string()),
+        ]));
+        assert!(Prisma::get_migrate_cmd(&app, &env, None).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+// Code relating to authenticating with private npm registries
+
+use crate::nixpacks::{app::App, environment::Environment, plan::phase::Phase};
+
+use super::NodeProvider;
+
+const NPM_TOKEN_ENV_VAR: &str = "NPM_TOKEN";
+const NPM_REGISTRIES_ENV_VAR: &str = "NPM_REGISTRIES";
+const DEFAULT_REGISTRY: &str = "https://registry.npmjs.org/";
+
+/// A registry from `NPM_REGISTRIES`, either `<url>` or `@scope=<url>`
+#[derive(Debug, PartialEq, Eq)]
+struct Registry {
+    scope: Option<String>,
+    url: String,
+}
+
+impl Registry {
+    /// npm keys auth settings by the registry url without its protocol
+    fn auth_key(&self) -> String {
+        let url = self
+            .url
+            .split_once("://")
+            .map_or(self.url.as_str(), |(_, url)| url);
+        format!("//{url}")
+    }
+}
+
+pub struct NpmRegistry;
+
+impl NpmRegistry {
+    fn get_registries(env: &Environment) -> Vec<Registry> {
+        let registries = match env.get_config_variable(NPM_REGISTRIES_ENV_VAR) {
+            Some(registries) => registries,
+            None => DEFAULT_REGISTRY.to_string(),
+        };
+
+        registries
+            .split(',')
+            .map(str::trim)
+            .filter(|registry| !registry.is_empty())
+            .map(|registry| {
+                let (scope, url) = match registry.split_once('=') {
+                    Some((scope, url)) => (Some(scope.trim().to_string()), url.trim()),
+                    None => (None, registry),
+                };
+                Registry {
+                    scope,
+                    url: format!("{}/", url.trim_end_matches('/')),
+                }
+            })
+            .collect()
+    }
+
+    fn uses_yarnrc(app: &App) -> bool {
+        NodeProvider::get_package_manager(app) == "yarn" && app.includes_file(".yarnrc.yml")
+    }
+
+    /// The user config to write the auth settings to, if `NPM_TOKEN` is set.
+    /// Projects that already configure auth keep their own config.
+    fn get_user_config(app: &App, env: &Environment) -> Option<&'static str> {
+        env.get_variable(NPM_TOKEN_ENV_VAR)?;
+
+        let (project_config, auth_setting, user_config) = if NpmRegistry::uses_yarnrc(app) {
+            (".yarnrc.yml", "npmAuthToken", "/root/.yarnrc.yml")
+        } else {
+            (".npmrc", "_authToken", "/root/.npmrc")
+        };
+        if app
+            .read_file(project_config)
+            .is_ok_and(|config| config.contains(auth_setting))
+        {
+            return None;
+        }
+
+        Some(user_config)
+    }
+
+    /// Writes a user config that reads the token from `NPM_TOKEN` when the package manager runs.
+    /// It is run right before installing and removed by the cleanup command right after.
+    pub fn get_auth_setup_cmd(app: &App, env: &Environment) -> Option<String> {
+        let user_config = NpmRegistry::get_user_config(app, env)?;
+
+        let registries = NpmRegistry::get_registries(env);
+        let lines = if NpmRegistry::uses_yarnrc(app) {
+            NpmRegistry::get_yarnrc_lines(&registries)
+        } else {
+            NpmRegistry::get_npmrc_lines(&registries)
+        };
+
+        Some(format!(
+            "printf '%s\\n' {} > {user_config}",
+            lines
+                .iter()
+                .map(|line| format!("'{line}'"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ))
+    }
+
+    /// Removes the user config, so nothing at runtime expects `NPM_TOKEN` to be set
+    pub fn get_auth_cleanup_cmd(app: &App, env: &Environment) -> Option<String> {
+        let user_config = NpmRegistry::get_user_config(app, env)?;
+        Some(format!("rm -f {user_config}"))
+    }
+
+    /// Adds `cmd` to the phase, authenticated only while it runs
+    pub fn add_authenticated_cmd(
+        phase: &mut Phase,
+        app: &App,
+        env: &Environment,
+        cmd: impl Into<String>,
+    ) {
+        if let Some(auth_cmd) = NpmRegistry::get_auth_setup_cmd(app, env) {
+            phase.add_cmd(auth_cmd);
+        }
+        phase.add_cmd(cmd);
+        if let Some(cleanup_cmd) = NpmRegistry::get_auth_cleanup_cmd(app, env) {
+            phase.add_cmd(cleanup_cmd);
+        }
+    }
+
+    fn get_npmrc_lines(registries: &[Registry]) -> Vec<String> {
+        let mut lines = Vec::new();
+        for registry in registries {
+            match &registry.scope {
+                Some(scope) => lines.push(format!("{scope}:registry={}", registry.url)),
+                None if registry.url != DEFAULT_REGISTRY => {
+                    lines.push(format!("registry={}", registry.url));
+                }
+                None => {}
+            }
+            lines.push(format!(
+                "{}:_authToken=${{{NPM_TOKEN_ENV_VAR}}}",
+                registry.auth_key()
+            ));
+        }
+
+        lines
+    }
+
+    fn get_yarnrc_lines(registries: &[Registry]) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut scope_lines = Vec::new();
+        for registry in registries {
+            match &registry.scope {
+                Some(scope) => {
+                    scope_lines.push(format!("  {}:", scope.trim_start_matches('@')));
+                    scope_lines.push(format!("    npmRegistryServer: \"{}\"", registry.url));
+                    scope_lines.push(format!("    npmAuthToken: \"${{{NPM_TOKEN_ENV_VAR}}}\""));
+                }
+                None => {
+                    if registry.url != DEFAULT_REGISTRY {
+                        lines.push(format!("npmRegistryServer: \"{}\"", registry.url));
+                    }
+                    lines.push(format!("npmAuthToken: \"${{{NPM_TOKEN_ENV_VAR}}}\""));
+                }
+            }
+        }
+
+        if !scope_lines.is_empty() {
+            lines.push("npmScopes:".to_string());
+            lines.extend(scope_lines);
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_no_token() {
+        let app = App::new("examples/node-prisma").unwrap();
+        assert_eq!(
+            NpmRegistry::get_auth_setup_cmd(&app, &Environment::default()),
+            None
+        );
+        assert_eq!(
+            NpmRegistry::get_auth_cleanup_cmd(&app, &Environment::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_npmrc_auth() {
+        let app = App::new("examples/node-prisma").unwrap();
+        let env = Environment::new(BTreeMap::from([
+            ("NPM_TOKEN".to_string(), "secret".to_string()),
+            (
+                "NIXPACKS_NPM_REGISTRIES".to_string(),
+                "@acme=https://npm.pkg.github.com, https://registry.npmjs.org".to_string(),
+            ),
+        ]));
+
+        let cmd = NpmRegistry::get_auth_setup_cmd(&app, &env).unwrap();
+        assert_eq!(
+            cmd,
+            "printf '%s\\n' '@acme:registry=https://npm.pkg.github.com/' '//npm.pkg.github.com/:_authToken=${NPM_TOKEN}' '//registry.npmjs.org/:_authToken=${NPM_TOKEN}' > /root/.npmrc"
+        );
+        assert!(!cmd.contains("secret"));
+        assert_eq!(
+            NpmRegistry::get_auth_cleanup_cmd(&app, &env),
+            Some("rm -f /root/.npmrc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_yarnrc_auth() {
+        let registries = vec![Registry {
+            scope: Some("@acme".to_string()),
+            url: "https://npm.pkg.github.com/".to_string(),
+        }];
+        assert_eq!(
+            NpmRegistry::get_yarnrc_lines(&registries),
+            vec![
+                "npmScopes:",
+                "  acme:",
+                "    npmRegistryServer: \"https://npm.pkg.github.com/\"",
+                "    npmAuthToken: \"${NPM_TOKEN}\""
+            ]
+        );
+    }
+}
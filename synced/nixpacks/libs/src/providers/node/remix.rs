@@ -0,0 +1,136 @@
+// Code relating to Remix and React Router framework apps
+
+use regex::Regex;
+
+use crate::nixpacks::app::App;
+
+use super::{NodeProvider, PackageJson};
+
+const REMIX_CONFIG_FILES: &[&str] = &["remix.config.js", "remix.config.mjs", "remix.config.cjs"];
+const REACT_ROUTER_CONFIG_FILES: &[&str] = &["react-router.config.ts", "react-router.config.js"];
+const VITE_CONFIG_FILES: &[&str] = &[
+    "vite.config.ts",
+    "vite.config.js",
+    "vite.config.mts",
+    "vite.config.mjs",
+];
+
+pub struct Remix;
+
+impl Remix {
+    /// React Router v7 in framework mode, the successor to Remix
+    pub fn is_react_router(app: &App) -> bool {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        package_json.has_dependency("@react-router/dev")
+            || REACT_ROUTER_CONFIG_FILES
+                .iter()
+                .any(|config_file| app.includes_file(config_file))
+    }
+
+    pub fn is_remix(app: &App) -> bool {
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        package_json.has_dependency("@remix-run/dev")
+            || REMIX_CONFIG_FILES
+                .iter()
+                .any(|config_file| app.includes_file(config_file))
+            || Remix::uses_vite(app)
+    }
+
+    /// Remix apps built with the Vite plugin write the server build to `build/server`
+    fn uses_vite(app: &App) -> bool {
+        Remix::is_react_router(app)
+            || VITE_CONFIG_FILES.iter().any(|config_file| {
+                app.read_file(config_file)
+                    .is_ok_and(|config| config.contains("@remix-run/dev"))
+            })
+    }
+
+    pub fn get_build_cmd(app: &App) -> Option<String> {
+        if !Remix::is_remix(app) {
+            return None;
+        }
+
+        let dlx = NodeProvider::get_package_manager_dlx_command(app);
+        Some(if Remix::is_react_router(app) {
+            format!("{dlx} react-router build")
+        } else if Remix::uses_vite(app) {
+            format!("{dlx} remix vite:build")
+        } else {
+            format!("{dlx} remix build")
+        })
+    }
+
+    pub fn get_server_build_path(app: &App) -> String {
+        if Remix::uses_vite(app) {
+            return "build/server/index.js".to_string();
+        }
+
+        let r = Regex::new(r#"serverBuildPath:\s*['"`](.*?)['"`]"#).unwrap();
+        REMIX_CONFIG_FILES
+            .iter()
+            .find_map(|config_file| app.read_file(config_file).ok())
+            .and_then(|config| {
+                r.captures(&config)
+                    .and_then(|c| c.get(1))
+                    .map(|path| path.as_str().to_string())
+            })
+            .unwrap_or("build/index.js".to_string())
+    }
+
+    /// Apps with their own server (e.g. Express) have no serve package, and use their start script
+    pub fn get_start_cmd(app: &App) -> Option<String> {
+        if !Remix::is_remix(app) {
+            return None;
+        }
+
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        let serve =
+            if Remix::is_react_router(app) && package_json.has_dependency("@react-router/serve") {
+                "react-router-serve"
+            } else if package_json.has_dependency("@remix-run/serve") {
+                "remix-serve"
+            } else {
+                return None;
+            };
+
+        Some(format!(
+            "{} {serve} {}",
+            NodeProvider::get_package_manager_dlx_command(app),
+            Remix::get_server_build_path(app)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_remix_vite() {
+        let app = App::new("examples/node-remix-vite").unwrap();
+        assert!(Remix::is_remix(&app));
+        assert!(!Remix::is_react_router(&app));
+        assert_eq!(
+            Remix::get_build_cmd(&app),
+            Some("npx remix vite:build".to_string())
+        );
+        assert_eq!(
+            Remix::get_start_cmd(&app),
+            Some("npx remix-serve build/server/index.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_react_router() {
+        let app = App::new("examples/node-react-router-framework").unwrap();
+        assert!(Remix::is_react_router(&app));
+        assert_eq!(
+            Remix::get_build_cmd(&app),
+            Some("npx react-router build".to_string())
+        );
+        assert_eq!(
+            Remix::get_start_cmd(&app),
+            Some("npx react-router-serve build/server/index.js".to_string())
+        );
+    }
+}
@@ -0,0 +1,241 @@
+// Code relating to Rush monorepos
+
+use serde::{Deserialize, Serialize};
+
+use crate::nixpacks::{app::App, environment::Environment};
+
+use super::PackageJson;
+
+const RUSH_APP_NAME_ENV_VAR: &str = "RUSH_APP_NAME";
+const INSTALL_RUN_RUSH_SCRIPT: &str = "common/scripts/install-run-rush.js";
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RushJson {
+    pub rush_version: Option<String>,
+    #[serde(default)]
+    pub projects: Vec<RushProject>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RushProject {
+    pub package_name: String,
+    pub project_folder: String,
+}
+
+pub struct Rush;
+
+impl Rush {
+    pub fn is_rush_repo(app: &App, env: &Environment) -> bool {
+        app.includes_file("rush.json") && Rush::get_project(app, env).is_some()
+    }
+
+    /// rush.json allows comments and trailing commas, which serde_json can't parse
+    pub fn get_config(app: &App) -> Option<RushJson> {
+        let contents = app.read_file("rush.json").ok()?;
+        serde_json::from_str(&strip_trailing_commas(&strip_json_comments(&contents))).ok()
+    }
+
+    /// The project set with `RUSH_APP_NAME`, or the only project with a start script
+    pub fn get_project(app: &App, env: &Environment) -> Option<RushProject> {
+        let config = Rush::get_config(app)?;
+        if let Some(app_name) = env.get_config_variable(RUSH_APP_NAME_ENV_VAR) {
+            return config
+                .projects
+                .into_iter()
+                .find(|project| project.package_name == app_name);
+        }
+
+        let mut projects: Vec<RushProject> = config
+            .projects
+            .into_iter()
+            .filter(|project| {
+                Rush::read_package_json(app, project)
+                    .scripts
+                    .is_some_and(|scripts| scripts.contains_key("start"))
+            })
+            .collect();
+        if projects.len() == 1 {
+            projects.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Prefers the launcher committed by `rush init`, which installs the version from rush.json
+    fn get_rush_cmd(app: &App) -> String {
+        if app.includes_file(INSTALL_RUN_RUSH_SCRIPT) {
+            return format!("node {INSTALL_RUN_RUSH_SCRIPT}");
+        }
+
+        match Rush::get_config(app).and_then(|config| config.rush_version) {
+            Some(version) => format!("npx @microsoft/rush@{version}"),
+            None => "npx @microsoft/rush".to_string(),
+        }
+    }
+
+    pub fn get_install_cmd(app: &App, env: &Environment) -> Option<String> {
+        let project = Rush::get_project(app, env)?;
+
+        Some(format!(
+            "{} install --to {}",
+            Rush::get_rush_cmd(app),
+            project.package_name
+        ))
+    }
+
+    pub fn get_build_cmd(app: &App, env: &Environment) -> Option<String> {
+        let project = Rush::get_project(app, env)?;
+
+        Some(format!(
+            "{} build --to {}",
+            Rush::get_rush_cmd(app),
+            project.package_name
+        ))
+    }
+
+    pub fn get_start_cmd(app: &App, env: &Environment) -> Option<String> {
+        let project = Rush::get_project(app, env)?;
+        let package_json = Rush::read_package_json(app, &project);
+        let folder = project.project_folder.trim_end_matches('/');
+
+        if package_json
+            .scripts
+            .is_some_and(|scripts| scripts.contains_key("start"))
+        {
+            return Some(format!("cd {folder} && npm run start"));
+        }
+
+        package_json
+            .main
+            .map(|main| format!("cd {folder} && node {main}"))
+    }
+
+    fn read_package_json(app: &App, project: &RushProject) -> PackageJson {
+        app.read_json(&format!(
+            "{}/package.json",
+            project.project_folder.trim_end_matches('/')
+        ))
+        .unwrap_or_default()
+    }
+}
+
+/// Removes `//` and `/* */` comments outside of strings
+fn strip_json_comments(contents: &str) -> String {
+    let mut stripped = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    stripped.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            while chars.next_if(|c| *c != '\n').is_some() {}
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    chars.next();
+                    break;
+                }
+            }
+        } else {
+            in_string = c == '"';
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+/// Removes commas directly before a closing `}` or `]`, outside of strings
+fn strip_trailing_commas(contents: &str) -> String {
+    let mut stripped = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    stripped.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == ',' {
+            let mut whitespace = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_whitespace()) {
+                whitespace.push(c);
+            }
+            if !matches!(chars.peek(), Some('}' | ']')) {
+                stripped.push(c);
+            }
+            stripped.push_str(&whitespace);
+        } else {
+            in_string = c == '"';
+            stripped.push(c);
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_rush() {
+        let app = App::new("examples/node-rush").unwrap();
+        let env = Environment::default();
+
+        assert!(Rush::is_rush_repo(&app, &env));
+        assert_eq!(
+            Rush::get_install_cmd(&app, &env),
+            Some("npx @microsoft/rush@5.133.4 install --to @node-rush/server".to_string())
+        );
+        assert_eq!(
+            Rush::get_build_cmd(&app, &env),
+            Some("npx @microsoft/rush@5.133.4 build --to @node-rush/server".to_string())
+        );
+        assert_eq!(
+            Rush::get_start_cmd(&app, &env),
+            Some("cd apps/server && npm run start".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_RUSH_APP_NAME".to_string(),
+            "@node-rush/missing".to_string(),
+        )]));
+        assert!(!Rush::is_rush_repo(&app, &env));
+    }
+
+    #[test]
+    fn test_strip_json_comments() {
+        assert_eq!(
+            strip_json_comments(
+                "{\n  // comment\n  \"a\": \"//not a comment\", /* b */ \"c\": 1\n}"
+            ),
+            "{\n  \n  \"a\": \"//not a comment\",  \"c\": 1\n}"
+        );
+    }
+
+    #[test]
+    fn test_strip_trailing_commas() {
+        assert_eq!(
+            strip_trailing_commas("{\n  \"a\": [1, 2,],\n  \"b\": \",}\",\n}"),
+            "{\n  \"a\": [1, 2],\n  \"b\": \",}\"\n}"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{nixpacks::app::App, providers::node::PackageJson};
+
+/// Create React App always builds a static site, as react-scripts has no server
+pub struct CraSpaProvider {}
+
+impl CraSpaProvider {
+    pub fn is_cra(app: &App) -> bool {
+        let pkg: PackageJson = app.read_json("package.json").unwrap_or_default();
+        pkg.has_dependency("react-scripts")
+    }
+
+    pub fn get_output_directory(app: &App) -> Option<String> {
+        if CraSpaProvider::is_cra(app) {
+            Some(String::from("build"))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cra() {
+        let app = App::new("examples/node-cra").unwrap();
+        assert!(CraSpaProvider::is_cra(&app));
+        assert_eq!(
+            CraSpaProvider::get_output_directory(&app),
+            Some("build".to_string())
+        );
+    }
+}
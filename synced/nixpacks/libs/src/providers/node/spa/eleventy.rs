@@ -0,0 +1,72 @@
+use regex::Regex;
+
+use crate::{nixpacks::app::App, providers::node::NodeProvider};
+
+const ELEVENTY_CONFIG_FILES: &[&str] = &[
+    "eleventy.config.js",
+    "eleventy.config.mjs",
+    "eleventy.config.cjs",
+    ".eleventy.js",
+    ".eleventy.cjs",
+];
+
+/// Eleventy generates a static site, so its output is served rather than run
+pub struct EleventySpaProvider {}
+
+impl EleventySpaProvider {
+    /// Eleventy is usually a devDependency, so it is detected from its config file instead
+    fn get_config_file(app: &App) -> Option<&'static str> {
+        ELEVENTY_CONFIG_FILES
+            .iter()
+            .copied()
+            .find(|file| app.includes_file(file))
+    }
+
+    /// Runs the installed Eleventy with the app's package manager
+    pub fn get_build_cmd(app: &App) -> Option<String> {
+        EleventySpaProvider::get_config_file(app).map(|_| {
+            format!(
+                "{} eleventy",
+                NodeProvider::get_package_manager_exec_command(app)
+            )
+        })
+    }
+
+    /// Reads `dir.output` from the config, which defaults to `_site`
+    pub fn get_output_directory(app: &App) -> Option<String> {
+        let config = app
+            .read_file(EleventySpaProvider::get_config_file(app)?)
+            .unwrap_or_default();
+        let output_re = Regex::new(r#"output:\s*['"`]([^'"`]+)['"`]"#).unwrap();
+        Some(
+            output_re
+                .captures(&config)
+                .map_or_else(|| "_site".to_string(), |captures| captures[1].to_string()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eleventy() {
+        let app = App::new("examples/node-eleventy").unwrap();
+        assert_eq!(
+            EleventySpaProvider::get_build_cmd(&app),
+            Some("npx --no-install eleventy".to_string())
+        );
+        assert_eq!(
+            EleventySpaProvider::get_output_directory(&app),
+            Some("dist".to_string())
+        );
+    }
+
+    #[test]
+    fn test_not_eleventy() {
+        let app = App::new("examples/node-cra").unwrap();
+        assert_eq!(EleventySpaProvider::get_build_cmd(&app), None);
+        assert_eq!(EleventySpaProvider::get_output_directory(&app), None);
+    }
+}
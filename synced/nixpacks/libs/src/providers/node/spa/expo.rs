@@ -0,0 +1,82 @@
+use serde::Deserialize;
+
+use crate::{nixpacks::app::App, providers::node::PackageJson};
+
+#[derive(Deserialize, Default, Debug)]
+struct AppJson {
+    expo: Option<ExpoConfig>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+struct ExpoConfig {
+    web: Option<ExpoWebConfig>,
+}
+
+#[derive(Deserialize, Default, Debug)]
+struct ExpoWebConfig {
+    output: Option<String>,
+}
+
+/// Expo apps with web support are exported to a static site, rather than running the Metro dev
+/// server that `expo start` launches
+pub struct ExpoSpaProvider {}
+
+impl ExpoSpaProvider {
+    pub fn is_expo(app: &App) -> bool {
+        let pkg: PackageJson = app.read_json("package.json").unwrap_or_default();
+        let app_json: AppJson = app.read_json("app.json").unwrap_or_default();
+        app_json.expo.is_some()
+            && pkg.has_dependency("expo")
+            && pkg.has_dependency("react-native-web")
+    }
+
+    /// The `server` output also builds API routes, which need a server to run
+    fn is_static(app: &App) -> bool {
+        let app_json: AppJson = app.read_json("app.json").unwrap_or_default();
+        let output = app_json
+            .expo
+            .and_then(|expo| expo.web)
+            .and_then(|web| web.output);
+        ExpoSpaProvider::is_expo(app) && output.as_deref() != Some("server")
+    }
+
+    pub fn get_build_cmd(app: &App) -> Option<String> {
+        ExpoSpaProvider::is_static(app).then(|| "npx expo export --platform web".to_string())
+    }
+
+    pub fn get_output_directory(app: &App) -> Option<String> {
+        ExpoSpaProvider::is_static(app).then(|| String::from("dist"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nixpacks::environment::Environment, providers::node::NodeProvider};
+
+    #[test]
+    fn test_expo() {
+        let app = App::new("examples/node-expo-web").unwrap();
+        assert!(ExpoSpaProvider::is_expo(&app));
+        assert_eq!(
+            ExpoSpaProvider::get_build_cmd(&app),
+            Some("npx expo export --platform web".to_string())
+        );
+        assert_eq!(
+            ExpoSpaProvider::get_output_directory(&app),
+            Some("dist".to_string())
+        );
+        assert!(
+            NodeProvider::get_start_cmd(&app, &Environment::default(), None)
+                .unwrap()
+                .is_some_and(|cmd| cmd.starts_with("exec caddy run"))
+        );
+    }
+
+    #[test]
+    fn test_not_expo() {
+        let app = App::new("examples/node-cra").unwrap();
+        assert!(!ExpoSpaProvider::is_expo(&app));
+        assert_eq!(ExpoSpaProvider::get_build_cmd(&app), None);
+    }
+}
@@ -5,6 +5,11 @@ use crate::nixpacks::{
     plan::phase::Phase,
 };
 
+use super::{astro::Astro, nuxt::Nuxt, nx::Nx, sveltekit::SvelteKit};
+
+pub mod cra;
+pub mod eleventy;
+pub mod expo;
 pub mod vite;
 
 const NIX_ARCHIVE: &str = "ba913eda2df8eb72147259189d55932012df6301";
@@ -12,51 +17,74 @@ const NIX_ARCHIVE: &str = "ba913eda2df8eb72147259189d55932012df6301";
 pub struct SpaProvider {}
 
 impl SpaProvider {
-    pub fn is_spa(app: &App) -> bool {
-        // other ones will be implemented here
+    pub fn is_spa(app: &App, env: &Environment, nx: Option<&Nx>) -> bool {
         vite::ViteSpaProvider::is_vite(app)
+            || SpaProvider::get_static_output_dir(app, env, nx).is_some()
+            || env.get_config_variable("SPA_OUT_DIR").is_some()
+    }
+
+    /// The output directory of frameworks that are known to build a static site
+    fn get_static_output_dir(app: &App, env: &Environment, nx: Option<&Nx>) -> Option<String> {
+        nx.and_then(|nx| nx.get_nx_static_output_dir(app, env))
+            .or_else(|| SvelteKit::get_static_output_dir(app))
+            .or_else(|| Astro::get_static_output_dir(app))
+            .or_else(|| Nuxt::get_static_output_dir(app, env))
+            .or_else(|| cra::CraSpaProvider::get_output_directory(app))
+            .or_else(|| expo::ExpoSpaProvider::get_output_directory(app))
+            .or_else(|| eleventy::EleventySpaProvider::get_output_directory(app))
     }
 
-    pub fn caddy_phase(app: &App, env: &Environment) -> Option<Phase> {
+    pub fn caddy_phase(app: &App, env: &Environment, nx: Option<&Nx>) -> Option<Phase> {
         if let Some(s) = env.get_config_variable("SPA_CADDY") {
             if s.to_lowercase() == "false" || s.to_lowercase() == "0" {
                 return None;
             }
         }
-        if Self::is_spa(app)
-            && (vite::ViteSpaProvider::caddy_allowlist(app)
-                || env.get_config_variable("SPA_OUT_DIR").is_some())
+        // Setting the output directory opts any app in to being served as a static site
+        if SpaProvider::get_static_output_dir(app, env, nx).is_some()
+            || env.get_config_variable("SPA_OUT_DIR").is_some()
+            || (vite::ViteSpaProvider::is_vite(app) && vite::ViteSpaProvider::caddy_allowlist(app))
         {
-            let mut caddy = Phase::new("caddy");
-            caddy.set_nix_archive(String::from(NIX_ARCHIVE)); // caddy 2.0.4
-            caddy.add_nix_pkgs(&[Pkg::new("caddy")]);
-            caddy.add_cmd(format!(
-                "caddy fmt --overwrite {}",
-                app.asset_path("Caddyfile")
-            ));
-            caddy.depends_on_phase("setup");
-            return Some(caddy);
+            return Some(SpaProvider::new_caddy_phase(app));
         }
         None
     }
 
+    /// Installs Caddy to serve `NIXPACKS_SPA_OUTPUT_DIR`. Also used by other providers that
+    /// build a static site, e.g. MkDocs.
+    pub fn new_caddy_phase(app: &App) -> Phase {
+        let mut caddy = Phase::new("caddy");
+        caddy.set_nix_archive(String::from(NIX_ARCHIVE)); // caddy 2.0.4
+        caddy.add_nix_pkgs(&[Pkg::new("caddy")]);
+        caddy.add_cmd(format!(
+            "caddy fmt --overwrite {}",
+            app.asset_path("Caddyfile")
+        ));
+        caddy.depends_on_phase("setup");
+        caddy
+    }
+
+    pub fn caddy_start_command(app: &App) -> String {
+        format!(
+            "exec caddy run --config {} --adapter caddyfile 2>&1",
+            app.asset_path("Caddyfile")
+        )
+    }
+
     pub fn static_assets() -> StaticAssets {
         static_asset_list! {
             "Caddyfile" => include_str!("Caddyfile")
         }
     }
 
-    pub fn get_output_directory(app: &App) -> String {
-        // other ones will be implemented here
-        vite::ViteSpaProvider::get_output_directory(app)
+    pub fn get_output_directory(app: &App, env: &Environment, nx: Option<&Nx>) -> String {
+        SpaProvider::get_static_output_dir(app, env, nx)
+            .unwrap_or_else(|| vite::ViteSpaProvider::get_output_directory(app))
     }
 
-    pub fn start_command(app: &App, env: &Environment) -> Option<String> {
-        if Self::caddy_phase(app, env).is_some() {
-            Some(format!(
-                "exec caddy run --config {} --adapter caddyfile 2>&1",
-                app.asset_path("Caddyfile")
-            ))
+    pub fn start_command(app: &App, env: &Environment, nx: Option<&Nx>) -> Option<String> {
+        if Self::caddy_phase(app, env, nx).is_some() {
+            Some(SpaProvider::caddy_start_command(app))
         } else {
             None
         }
@@ -0,0 +1,85 @@
+// Code relating to SvelteKit apps
+
+use regex::Regex;
+
+use crate::nixpacks::app::App;
+
+const SVELTE_CONFIG_FILES: &[&str] = &["svelte.config.js", "svelte.config.ts"];
+const DEFAULT_OUTPUT_DIR: &str = "build";
+
+pub struct SvelteKit;
+
+impl SvelteKit {
+    fn read_config(app: &App) -> Option<String> {
+        SVELTE_CONFIG_FILES
+            .iter()
+            .find_map(|config_file| app.read_file(config_file).ok())
+    }
+
+    /// The adapter imported by svelte.config, e.g. `node` for `@sveltejs/adapter-node`
+    pub fn get_adapter(app: &App) -> Option<String> {
+        let config = SvelteKit::read_config(app)?;
+        let r = Regex::new(r#"['"]@sveltejs/adapter-([a-z-]+)['"]"#).unwrap();
+        r.captures(&config)
+            .and_then(|c| c.get(1))
+            .map(|adapter| adapter.as_str().to_string())
+    }
+
+    /// adapter-node writes the server to `out`, adapter-static writes the site to `pages`
+    fn get_adapter_option(app: &App, option: &str) -> String {
+        let config = SvelteKit::read_config(app).unwrap_or_default();
+        let r = Regex::new(&format!(r#"\b{option}:\s*['"`](.*?)['"`]"#)).unwrap();
+        r.captures(&config)
+            .and_then(|c| c.get(1))
+            .map_or(DEFAULT_OUTPUT_DIR.to_string(), |dir| {
+                dir.as_str().trim_end_matches('/').to_string()
+            })
+    }
+
+    pub fn get_start_cmd(app: &App) -> Option<String> {
+        if SvelteKit::get_adapter(app)? != "node" {
+            return None;
+        }
+
+        Some(format!(
+            "node {}/index.js",
+            SvelteKit::get_adapter_option(app, "out")
+        ))
+    }
+
+    /// The directory of a site built with adapter-static, which is served like a SPA
+    pub fn get_static_output_dir(app: &App) -> Option<String> {
+        if SvelteKit::get_adapter(app)? != "static" {
+            return None;
+        }
+
+        Some(SvelteKit::get_adapter_option(app, "pages"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_adapter_node() {
+        let app = App::new("examples/node-sveltekit-node").unwrap();
+        assert_eq!(SvelteKit::get_adapter(&app), Some("node".to_string()));
+        assert_eq!(
+            SvelteKit::get_start_cmd(&app),
+            Some("node build/index.js".to_string())
+        );
+        assert_eq!(SvelteKit::get_static_output_dir(&app), None);
+    }
+
+    #[test]
+    fn test_adapter_static() {
+        let app = App::new("examples/node-sveltekit-static").unwrap();
+        assert_eq!(SvelteKit::get_adapter(&app), Some("static".to_string()));
+        assert_eq!(SvelteKit::get_start_cmd(&app), None);
+        assert_eq!(
+            SvelteKit::get_static_output_dir(&app),
+            Some("public".to_string())
+        );
+    }
+}
@@ -30,6 +30,10 @@ pub fn pnpm_workspaces(app: &App) -> Result<Vec<String>> {
     Ok(workspaces.packages)
 }
 
+const TURBO_APP_NAME_ENV_VAR: &str = "TURBO_APP_NAME";
+const TURBO_PRUNE_ENV_VAR: &str = "TURBO_PRUNE";
+const TURBO_PRUNE_OUT_DIR: &str = "/tmp/turbo-prune";
+
 pub struct Turborepo;
 
 impl Turborepo {
@@ -59,9 +63,14 @@ impl Turborepo {
     ) -> Result<Option<String>, Box<dyn Error>> {
         let turbo_cfg = Turborepo::get_config(app)?;
         let dlx = NodeProvider::get_package_manager_dlx_command(app);
+        let app_name = Turborepo::get_selected_app_name(app, env);
         if let Some(build_cmd) = Turborepo::get_build_cmd(&turbo_cfg) {
+            // Only build the selected app and the packages it depends on
+            if let Some(app_name) = app_name {
+                return Ok(Some(format!("{dlx} turbo run build --filter={app_name}")));
+            }
             return Ok(Some(build_cmd));
-        } else if let Some(app_name) = Turborepo::get_app_name(env) {
+        } else if let Some(app_name) = app_name {
             return Ok(Some(format!("{dlx} turbo run {app_name}:build")));
         }
 
@@ -78,28 +87,17 @@ impl Turborepo {
         package_json: &PackageJson,
     ) -> Result<Option<String>, Box<dyn Error>> {
         let turbo_cfg = Turborepo::get_config(app)?;
-        let app_name = Turborepo::get_app_name(env);
-        let pkg_manager = NodeProvider::get_package_manager(app);
+        let app_name = Turborepo::get_selected_app_name(app, env);
 
         if let Some(name) = app_name {
             if Turborepo::has_app(
                 app,
-                if pkg_manager == "pnpm" {
-                    pnpm_workspaces(app)?
-                } else if let Some(Workspaces::Array(workspaces)) = &package_json.workspaces {
-                    workspaces.clone()
-                } else {
-                    Vec::default()
-                },
+                Turborepo::get_workspace_globs(app, package_json)?,
                 &name,
             )? {
-                return Ok(Some(if pkg_manager == "pnpm" {
-                    format!("pnpm --filter {name} run start")
-                } else if pkg_manager == "yarn" {
-                    format!("{pkg_manager} workspace {name} run start")
-                } else {
-                    format!("{pkg_manager} --workspace {name} run start")
-                }));
+                return Ok(Some(NodeProvider::get_workspace_script_cmd(
+                    app, &name, "start",
+                )));
             }
             eprintln!("Warning: Turborepo app `{name}` not found");
         }
@@ -110,7 +108,91 @@ impl Turborepo {
     }
 
     pub fn get_app_name(env: &Environment) -> Option<String> {
-        env.get_config_variable("TURBO_APP_NAME")
+        env.get_config_variable(TURBO_APP_NAME_ENV_VAR)
+    }
+
+    /// The app set with `TURBO_APP_NAME`. When pruning, which only keeps a single app, the
+    /// only package in `apps/` with a start script is used if no app is set.
+    pub fn get_selected_app_name(app: &App, env: &Environment) -> Option<String> {
+        Turborepo::get_app_name(env).or_else(|| {
+            if env.is_config_variable_truthy(TURBO_PRUNE_ENV_VAR) {
+                Turborepo::detect_app_name(app)
+            } else {
+                None
+            }
+        })
+    }
+
+    fn get_workspace_globs(app: &App, package_json: &PackageJson) -> Result<Vec<String>> {
+        if NodeProvider::get_package_manager(app) == "pnpm" {
+            return pnpm_workspaces(app);
+        }
+
+        Ok(match &package_json.workspaces {
+            Some(Workspaces::Array(workspaces)) => workspaces.clone(),
+            _ => Vec::default(),
+        })
+    }
+
+    /// turbo isn't installed yet when pruning, so run the version the repo depends on
+    fn get_turbo_package(package_json: &PackageJson) -> String {
+        let version = [&package_json.dev_dependencies, &package_json.dependencies]
+            .into_iter()
+            .flatten()
+            .find_map(|deps| deps.get("turbo"));
+
+        match version {
+            Some(version) => format!("'turbo@{version}'"),
+            None => "turbo".to_string(),
+        }
+    }
+
+    pub fn detect_app_name(app: &App) -> Option<String> {
+        let mut app_names = NodeProvider::find_workspace_packages_with_script(
+            app,
+            &["apps/*".to_string()],
+            "start",
+        )
+        .ok()?;
+
+        // With several apps there is no way to tell which one to deploy
+        if app_names.len() == 1 {
+            app_names.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Runs `turbo prune` before installing, so only the selected app's packages and
+    /// dependencies are installed. Only the workspace packages left out of the pruned output
+    /// are removed, the rest of the repo (e.g. `.npmrc` or `.env` files) is kept.
+    pub fn get_prune_install_cmd(app: &App, env: &Environment) -> Option<String> {
+        if !Turborepo::is_turborepo(app) || !env.is_config_variable_truthy(TURBO_PRUNE_ENV_VAR) {
+            return None;
+        }
+
+        let app_name = Turborepo::get_selected_app_name(app, env)?;
+        let install_cmd = NodeProvider::get_install_command(app)?;
+        let package_json: PackageJson = app.read_json("package.json").unwrap_or_default();
+        let workspace_globs = Turborepo::get_workspace_globs(app, &package_json).ok()?;
+        Some(format!(
+            "{} {} prune {app_name} --out-dir={TURBO_PRUNE_OUT_DIR} && for dir in {}; do [ -e {TURBO_PRUNE_OUT_DIR}/$dir ] || rm -rf $dir; done && cp -a {TURBO_PRUNE_OUT_DIR}/. . && {install_cmd}",
+            Turborepo::get_download_dlx_command(app),
+            Turborepo::get_turbo_package(&package_json),
+            workspace_globs.join(" ")
+        ))
+    }
+
+    /// `yarn <bin>` only runs installed binaries, so the pinned turbo is downloaded with
+    /// `yarn dlx` on Yarn Berry and with npx on Yarn 1
+    fn get_download_dlx_command(app: &App) -> String {
+        if NodeProvider::get_package_manager(app) != "yarn" {
+            NodeProvider::get_package_manager_dlx_command(app)
+        } else if app.includes_file(".yarnrc.yml") {
+            "yarn dlx".to_string()
+        } else {
+            "npx".to_string()
+        }
     }
 
     pub fn has_app(app: &App, workspaces: Vec<String>, name: &str) -> Result<bool> {
@@ -120,8 +202,91 @@ impl Turborepo {
                 if file.ends_with(name) {
                     return Ok(true);
                 }
+
+                // Apps can also be referenced by their package name
+                let relative = app.strip_source_path(file.as_path())?;
+                let package_json: Option<PackageJson> = relative
+                    .to_str()
+                    .and_then(|dir| app.read_json(&format!("{dir}/package.json")).ok());
+                if package_json
+                    .is_some_and(|package_json| package_json.name.as_deref() == Some(name))
+                {
+                    return Ok(true);
+                }
             }
         }
         Ok(false)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_selected_app() -> Result<()> {
+        let app = App::new("examples/node-turborepo")?;
+
+        assert_eq!(Turborepo::detect_app_name(&app), Some("web".to_string()));
+        // The detected app is only used when pruning
+        assert_eq!(
+            Turborepo::get_actual_build_cmd(&app, &Environment::default()).unwrap(),
+            Some("npx turbo run build".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_TURBO_APP_NAME".to_string(),
+            "web".to_string(),
+        )]));
+        assert_eq!(
+            Turborepo::get_actual_build_cmd(&app, &env).unwrap(),
+            Some("npx turbo run build --filter=web".to_string())
+        );
+        assert_eq!(
+            Turborepo::get_actual_start_cmd(&app, &env, &app.read_json("package.json")?).unwrap(),
+            Some("npm --workspace web run start".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_install() -> Result<()> {
+        let app = App::new("examples/node-turborepo")?;
+        assert_eq!(
+            Turborepo::get_prune_install_cmd(&app, &Environment::default()),
+            None
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_TURBO_PRUNE".to_string(),
+            "true".to_string(),
+        )]));
+        assert_eq!(
+            Turborepo::get_prune_install_cmd(&app, &env),
+            Some("npx 'turbo@2.1.3' prune web --out-dir=/tmp/turbo-prune && for dir in apps/* packages/*; do [ -e /tmp/turbo-prune/$dir ] || rm -rf $dir; done && cp -a /tmp/turbo-prune/. . && npm ci".to_string())
+        );
+        assert_eq!(
+            Turborepo::get_actual_build_cmd(&app, &env).unwrap(),
+            Some("npx turbo run build --filter=web".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_install_yarn() -> Result<()> {
+        let app = App::new("examples/node-turborepo-yarn")?;
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_TURBO_PRUNE".to_string(),
+            "true".to_string(),
+        )]));
+        assert_eq!(
+            Turborepo::get_prune_install_cmd(&app, &env),
+            Some("npx 'turbo@2.1.3' prune web --out-dir=/tmp/turbo-prune && for dir in apps/* packages/*; do [ -e /tmp/turbo-prune/$dir ] || rm -rf $dir; done && cp -a /tmp/turbo-prune/. . && yarn install --frozen-lockfile".to_string())
+        );
+
+        Ok(())
+    }
+}
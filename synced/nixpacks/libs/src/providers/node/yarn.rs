@@ -0,0 +1,102 @@
+// Code relating to Yarn Plug'n'Play installs
+
+use serde::Deserialize;
+
+use crate::nixpacks::{app::App, environment::EnvironmentVariables};
+
+use super::NodeProvider;
+
+// With the global cache disabled, Yarn copies what it downloads from this mirror into .yarn/cache
+const YARN_MIRROR_DIR: &str = "/root/.yarn/berry/cache";
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Yarnrc {
+    node_linker: Option<String>,
+}
+
+pub struct YarnPnp;
+
+impl YarnPnp {
+    /// PnP resolves packages from the zip archives in .yarn/cache instead of a node_modules folder.
+    /// It is the default linker in Yarn 2+, so it is used unless `nodeLinker` says otherwise.
+    pub fn is_pnp(app: &App) -> bool {
+        if NodeProvider::get_package_manager(app) != "yarn" || !app.includes_file(".yarnrc.yml") {
+            return false;
+        }
+
+        let yarnrc: Yarnrc = app.read_yaml(".yarnrc.yml").unwrap_or_default();
+        yarnrc
+            .node_linker
+            .map_or(true, |node_linker| node_linker == "pnp")
+    }
+
+    /// Zero-installs commit .yarn/cache, so nothing needs to be downloaded
+    pub fn is_zero_install(app: &App) -> bool {
+        YarnPnp::is_pnp(app) && app.includes_directory(".yarn/cache")
+    }
+
+    pub fn get_install_cmd(app: &App) -> Option<String> {
+        YarnPnp::is_zero_install(app)
+            .then(|| "yarn install --immutable --immutable-cache".to_string())
+    }
+
+    /// .yarn/cache is needed at runtime so it stays in the image, and the mirror is cached instead
+    pub fn get_cache_dir(app: &App) -> Option<String> {
+        (YarnPnp::is_pnp(app) && !YarnPnp::is_zero_install(app))
+            .then(|| YARN_MIRROR_DIR.to_string())
+    }
+
+    pub fn get_environment_variables(app: &App) -> EnvironmentVariables {
+        if YarnPnp::is_pnp(app) {
+            EnvironmentVariables::from([(
+                "YARN_ENABLE_GLOBAL_CACHE".to_string(),
+                "false".to_string(),
+            )])
+        } else {
+            EnvironmentVariables::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::nixpacks::environment::Environment;
+
+    #[test]
+    fn test_yarn_pnp_zero_install() {
+        let app = App::new("examples/node-yarn-pnp").unwrap();
+        assert!(YarnPnp::is_pnp(&app));
+        assert!(YarnPnp::is_zero_install(&app));
+        assert_eq!(
+            YarnPnp::get_install_cmd(&app),
+            Some("yarn install --immutable --immutable-cache".to_string())
+        );
+        assert_eq!(YarnPnp::get_cache_dir(&app), None);
+        assert_eq!(
+            NodeProvider::get_start_cmd(&app, &Environment::default(), None).unwrap(),
+            Some("yarn node index.js".to_string())
+        );
+    }
+
+    #[test]
+    fn test_yarn_pnp_default_linker() {
+        let app = App::new("examples/node-yarn-berry").unwrap();
+        assert!(YarnPnp::is_pnp(&app));
+        assert!(!YarnPnp::is_zero_install(&app));
+        assert_eq!(YarnPnp::get_install_cmd(&app), None);
+        assert_eq!(
+            YarnPnp::get_cache_dir(&app),
+            Some("/root/.yarn/berry/cache".to_string())
+        );
+    }
+
+    #[test]
+    fn test_not_yarn_pnp() {
+        let app = App::new("examples/node-prisma").unwrap();
+        assert!(!YarnPnp::is_pnp(&app));
+        assert_eq!(YarnPnp::get_install_cmd(&app), None);
+        assert!(YarnPnp::get_environment_variables(&app).is_empty());
+    }
+}
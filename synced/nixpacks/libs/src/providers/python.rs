@@ -12,23 +12,37 @@ use crate::{
     Pkg,
 };
 use anyhow::{bail, Context, Ok, Result};
+use path_slash::PathExt;
 use regex::{Match, Regex};
 use serde::Deserialize;
 use std::result::Result::Ok as OkResult;
-use std::{collections::HashMap, fs};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+};
 
-use super::{Provider, ProviderMetadata};
+use super::{node::spa::SpaProvider, Provider, ProviderMetadata};
 
 const DEFAULT_PYTHON_PKG_NAME: &str = "python3";
 const POETRY_VERSION: &str = "1.3.1";
 const PDM_VERSION: &str = "2.13.3";
 const UV_VERSION: &str = "0.4.30";
+const HATCH_VERSION: &str = "1.13.0";
 
 const VENV_LOCATION: &str = "/opt/venv";
+const HATCH_VENV_LOCATION: &str = "/opt/hatch";
+const CONDA_ENV_LOCATION: &str = "/opt/conda";
+const CONDA_PKGS_DIR: &str = "/root/.cache/conda/pkgs";
 const UV_CACHE_DIR: &str = "/root/.cache/uv";
 const PIP_CACHE_DIR: &str = "/root/.cache/pip";
 const PDM_CACHE_DIR: &str = "/root/.cache/pdm";
 const DEFAULT_POETRY_PYTHON_PKG_NAME: &str = "python3";
+const PYTHON_OPTIMIZE_ENV_VAR: &str = "PYTHON_OPTIMIZE";
+const PYTHON_STRIP_TESTS_ENV_VAR: &str = "PYTHON_STRIP_TESTS";
+
+// The 3.x versions that can be installed, and the one provided by the default package
+const AVAILABLE_PYTHON_MINOR_VERSIONS: &[u32] = &[8, 9, 10, 11, 12, 13];
+const DEFAULT_PYTHON_MINOR_VERSION: u32 = 11;
 
 const PYTHON_NIXPKGS_ARCHIVE: &str = "bc8f8d1be58e8c8383e683a06e1e1e57893fff87";
 const LEGACY_PYTHON_NIXPKGS_ARCHIVE: &str = "5148520bfab61f99fd25fb9ff7bfbb50dad3c9db";
@@ -40,7 +54,9 @@ enum PackageManagerType {
     Poetry,
     Pdm,
     Uv,
+    Hatch,
     Pipenv,
+    Conda,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -66,7 +82,9 @@ impl PackageManager {
                 "poetry" => Self::Specified(PackageManagerType::Poetry),
                 "pdm" => Self::Specified(PackageManagerType::Pdm),
                 "uv" => Self::Specified(PackageManagerType::Uv),
+                "hatch" => Self::Specified(PackageManagerType::Hatch),
                 "pipenv" => Self::Specified(PackageManagerType::Pipenv),
+                "conda" => Self::Specified(PackageManagerType::Conda),
                 "skip" => Self::Skip,
                 _ => {
                     eprintln!("Warning: Unknown package manager '{s}'. Using auto-detection.",);
@@ -88,11 +106,15 @@ impl PackageManager {
                         Action::InstallWith(PackageManagerType::Pdm)
                     } else if app.includes_file("uv.lock") {
                         Action::InstallWith(PackageManagerType::Uv)
+                    } else if PythonProvider::is_hatch(app) {
+                        Action::InstallWith(PackageManagerType::Hatch)
                     } else {
                         Action::InstallWith(PackageManagerType::PipSetuptools) // Default for pyproject.toml without lock files
                     }
                 } else if app.includes_file("Pipfile") {
                     Action::InstallWith(PackageManagerType::Pipenv)
+                } else if PythonProvider::get_conda_environment_file(app).is_some() {
+                    Action::InstallWith(PackageManagerType::Conda)
                 } else {
                     Action::NoInstallation // Default fallback
                 }
@@ -114,7 +136,8 @@ impl Provider for PythonProvider {
         let has_python = app.includes_file("main.py")
             || app.includes_file("requirements.txt")
             || app.includes_file("pyproject.toml")
-            || app.includes_file("Pipfile");
+            || app.includes_file("Pipfile")
+            || PythonProvider::get_conda_environment_file(app).is_some();
         Ok(has_python)
     }
 
@@ -123,12 +146,14 @@ impl Provider for PythonProvider {
         let is_using_postgres = PythonProvider::is_using_postgres(app, env)?;
         let is_poetry = app.includes_file("poetry.lock");
         let is_pdm = app.includes_file("pdm.lock");
+        let is_hatch = PythonProvider::is_hatch(app);
 
         Ok(ProviderMetadata::from(vec![
             (is_django, "django"),
             (is_using_postgres, "postgres"),
             (is_poetry, "poetry"),
             (is_pdm, "pdm"),
+            (is_hatch, "hatch"),
         ]))
     }
 
@@ -138,10 +163,43 @@ impl Provider for PythonProvider {
         let setup = self.setup(app, env)?.unwrap_or_default();
         plan.add_phase(setup);
 
-        let install = self.install(app, env)?.unwrap_or_default();
+        let mut install = self.install(app, env)?.unwrap_or_default();
+        if let Some(dependencies) = self.dependencies(app, env) {
+            install.depends_on_phase("dependencies");
+            plan.add_phase(dependencies);
+        }
         plan.add_phase(install);
 
-        if let Some(start) = self.start(app, env)? {
+        let build = self.build(app, env)?;
+        let has_build = build.is_some();
+        if let Some(build) = build {
+            plan.add_phase(build);
+        }
+
+        if env.is_config_variable_truthy(PYTHON_OPTIMIZE_ENV_VAR)
+            || env.is_config_variable_truthy(PYTHON_STRIP_TESTS_ENV_VAR)
+        {
+            plan.add_phase(PythonProvider::optimize(app, env, has_build));
+        }
+
+        if PythonProvider::is_mkdocs(app)? {
+            plan.add_phase(SpaProvider::new_caddy_phase(app));
+            plan.add_static_assets(SpaProvider::static_assets());
+            plan.add_variables(EnvironmentVariables::from([(
+                "NIXPACKS_SPA_OUTPUT_DIR".to_string(),
+                PythonProvider::get_mkdocs_site_dir(app),
+            )]));
+        }
+
+        if let Some(mut start) = self.start(app, env)? {
+            // Activates the conda environment, as some packages rely on its activation scripts
+            if PackageManager::from_env(env).resolve(app)
+                == Action::InstallWith(PackageManagerType::Conda)
+            {
+                start.cmd = start
+                    .cmd
+                    .map(|cmd| PythonProvider::get_conda_start_cmd(&cmd));
+            }
             plan.set_start_phase(start);
         }
 
@@ -196,6 +254,21 @@ impl Provider for PythonProvider {
             ]));
         }
 
+        if PackageManager::from_env(env).resolve(app)
+            == Action::InstallWith(PackageManagerType::Hatch)
+        {
+            plan.add_variables(EnvironmentVariables::from([
+                (
+                    "NIXPACKS_HATCH_VERSION".to_string(),
+                    HATCH_VERSION.to_string(),
+                ),
+                (
+                    "HATCH_ENV_TYPE_VIRTUAL_PATH".to_string(),
+                    VENV_LOCATION.to_string(),
+                ),
+            ]));
+        }
+
         Ok(Some(plan))
     }
 }
@@ -213,6 +286,9 @@ struct ProjectDecl {
     pub packages: Option<Vec<String>>,
     pub py_modules: Option<Vec<String>>,
     pub entry_points: Option<HashMap<String, String>>,
+    pub scripts: Option<BTreeMap<String, String>>,
+    #[serde(rename = "requires-python")]
+    pub requires_python: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -250,6 +326,12 @@ impl PythonProvider {
             pkgs.append(&mut vec![Pkg::new("pipenv")]);
         }
 
+        if PackageManager::from_env(env).resolve(app)
+            == Action::InstallWith(PackageManagerType::Conda)
+        {
+            pkgs.append(&mut vec![Pkg::new("micromamba")]);
+        }
+
         setup.add_nix_pkgs(&pkgs);
         setup.set_nix_archive(nix_archive);
 
@@ -295,6 +377,19 @@ impl PythonProvider {
                         );
                         let mut install_phase = Phase::install(Some(install_cmd));
 
+                        // Requirements that point at other files or the app itself need the full source
+                        let is_self_contained = app
+                            .read_file("requirements.txt")
+                            .unwrap_or_default()
+                            .lines()
+                            .map(str::trim)
+                            .all(|line| !line.starts_with('-') && !line.starts_with('.'));
+                        if env.is_config_variable_truthy(PYTHON_OPTIMIZE_ENV_VAR)
+                            && is_self_contained
+                        {
+                            install_phase.add_file_dependency("requirements.txt".to_string());
+                        }
+
                         install_phase.add_path(format!("{VENV_LOCATION}/bin"));
                         install_phase.add_cache_directory(PIP_CACHE_DIR.to_string());
 
@@ -305,7 +400,8 @@ impl PythonProvider {
                         let install_poetry =
                             "pip install poetry==$NIXPACKS_POETRY_VERSION".to_string();
                         let install_cmd = format!(
-                            "{create_env} && {activate_env} && {install_poetry} && poetry install --no-dev --no-interaction --no-ansi"
+                            "{create_env} && {activate_env} && {install_poetry} && {}",
+                            PythonProvider::get_poetry_install_cmd(env)
                         );
                         let mut install_phase = Phase::install(Some(install_cmd));
 
@@ -347,6 +443,44 @@ impl PythonProvider {
                         Ok(Some(install_phase))
                     }
 
+                    PackageManagerType::Hatch => {
+                        // Hatch skips creating an environment that already exists, so it is installed
+                        // into its own venv and creates the default environment at HATCH_ENV_TYPE_VIRTUAL_PATH
+                        let install_hatch = format!(
+                            "python -m venv --copies {HATCH_VENV_LOCATION} && {HATCH_VENV_LOCATION}/bin/pip install hatch==$NIXPACKS_HATCH_VERSION"
+                        );
+                        // With the dependencies installed in their own layer, only the project is left
+                        let install_cmd = if PythonProvider::uses_dependency_layer(app, env) {
+                            PythonProvider::get_hatch_project_install_cmd(app)
+                        } else {
+                            Some(format!(
+                                "{install_hatch} && {HATCH_VENV_LOCATION}/bin/hatch env create"
+                            ))
+                        };
+                        let mut install_phase = Phase::install(install_cmd);
+
+                        install_phase.add_path(format!("{VENV_LOCATION}/bin"));
+                        install_phase.add_cache_directory(PIP_CACHE_DIR.to_string());
+
+                        Ok(Some(install_phase))
+                    }
+
+                    PackageManagerType::Conda => {
+                        // Packages are copied out of the cached package directory, as they can't
+                        // be linked across the cache mount
+                        let environment_file = PythonProvider::get_conda_environment_file(app)
+                            .unwrap_or("environment.yml");
+                        let install_cmd = format!(
+                            "CONDA_PKGS_DIRS={CONDA_PKGS_DIR} micromamba create --yes --always-copy --prefix {CONDA_ENV_LOCATION} --file {environment_file}"
+                        );
+                        let mut install_phase = Phase::install(Some(install_cmd));
+
+                        install_phase.add_path(format!("{CONDA_ENV_LOCATION}/bin"));
+                        install_phase.add_cache_directory(CONDA_PKGS_DIR.to_string());
+
+                        Ok(Some(install_phase))
+                    }
+
                     PackageManagerType::PipSetuptools => {
                         let install_cmd = format!("{create_env} && {activate_env} && pip install --upgrade build setuptools && pip install .");
                         let mut install_phase = Phase::install(Some(install_cmd));
@@ -371,6 +505,13 @@ impl PythonProvider {
                         let install_cmd = format!("{create_env} && {activate_env} && {pipenv_cmd}");
                         let mut install_phase = Phase::install(Some(install_cmd));
 
+                        if env.is_config_variable_truthy(PYTHON_OPTIMIZE_ENV_VAR) {
+                            install_phase.add_file_dependency("Pipfile".to_string());
+                            if app.includes_file("Pipfile.lock") {
+                                install_phase.add_file_dependency("Pipfile.lock".to_string());
+                            }
+                        }
+
                         install_phase.add_path(format!("{VENV_LOCATION}/bin"));
                         install_phase.add_cache_directory(PIP_CACHE_DIR.to_string());
 
@@ -382,13 +523,168 @@ impl PythonProvider {
         }
     }
 
+    /// Installs only the dependencies from the project and lock files, so the layer is reused
+    /// until they change. The install phase then installs the project itself.
+    fn dependencies(&self, app: &App, env: &Environment) -> Option<Phase> {
+        if !PythonProvider::uses_dependency_layer(app, env) {
+            return None;
+        }
+
+        let create_env = format!("python -m venv --copies {VENV_LOCATION}");
+        let activate_env = format!(". {VENV_LOCATION}/bin/activate");
+        let (install_cmd, files, cache_dir) = match PackageManager::from_env(env).resolve(app) {
+            Action::InstallWith(PackageManagerType::Poetry) => (
+                format!(
+                    "{create_env} && {activate_env} && pip install poetry==$NIXPACKS_POETRY_VERSION && {} --no-root",
+                    PythonProvider::get_poetry_install_cmd(env)
+                ),
+                vec!["pyproject.toml", "poetry.lock", "poetry.toml"],
+                PIP_CACHE_DIR,
+            ),
+            Action::InstallWith(PackageManagerType::Pdm) => (
+                format!("{create_env} && {activate_env} && pip install pdm==$NIXPACKS_PDM_VERSION && pdm install --prod --no-self"),
+                vec!["pyproject.toml", "pdm.lock"],
+                PDM_CACHE_DIR,
+            ),
+            Action::InstallWith(PackageManagerType::Uv) => (
+                format!("{create_env} && {activate_env} && pip install uv==$NIXPACKS_UV_VERSION && uv sync --no-dev --frozen --no-install-project"),
+                vec!["pyproject.toml", "uv.lock"],
+                UV_CACHE_DIR,
+            ),
+            Action::InstallWith(PackageManagerType::Hatch) => (
+                format!("python -m venv --copies {HATCH_VENV_LOCATION} && {HATCH_VENV_LOCATION}/bin/pip install hatch==$NIXPACKS_HATCH_VERSION && {HATCH_VENV_LOCATION}/bin/hatch dep show requirements > /tmp/requirements.txt && {create_env} && {VENV_LOCATION}/bin/pip install -r /tmp/requirements.txt"),
+                vec!["pyproject.toml", "hatch.toml"],
+                PIP_CACHE_DIR,
+            ),
+            _ => return None,
+        };
+
+        let mut dependencies = Phase::new("dependencies");
+        dependencies.depends_on_phase("setup");
+        dependencies.add_cmd(install_cmd);
+        for file in files {
+            if app.includes_file(file) {
+                dependencies.add_file_dependency(file.to_string());
+            }
+        }
+        dependencies.add_path(format!("{VENV_LOCATION}/bin"));
+        dependencies.add_cache_directory(cache_dir.to_string());
+
+        Some(dependencies)
+    }
+
+    /// Poetry, PDM, uv and Hatch install the project along with its dependencies, which are split
+    /// into their own layer unless the project refers to local paths that aren't copied yet
+    fn uses_dependency_layer(app: &App, env: &Environment) -> bool {
+        if !env.is_config_variable_truthy(PYTHON_OPTIMIZE_ENV_VAR) {
+            return false;
+        }
+
+        let is_project_manager = matches!(
+            PackageManager::from_env(env).resolve(app),
+            Action::InstallWith(
+                PackageManagerType::Poetry
+                    | PackageManagerType::Pdm
+                    | PackageManagerType::Uv
+                    | PackageManagerType::Hatch
+            )
+        );
+        let pyproject = app.read_file("pyproject.toml").unwrap_or_default();
+        let local_path_re = Regex::new(r#"\bpath\s*=|\[tool\.uv\.workspace\]|file://"#).unwrap();
+
+        is_project_manager && !local_path_re.is_match(&pyproject)
+    }
+
+    /// Hatch doesn't install the project into an environment that already exists, so it is
+    /// installed with pip, in dev mode unless `dev-mode = false`
+    fn get_hatch_project_install_cmd(app: &App) -> Option<String> {
+        let config = ["pyproject.toml", "hatch.toml"]
+            .iter()
+            .map(|file| app.read_file(file).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let setting = |name: &str, value: &str| {
+            Regex::new(&format!(r"(?m)^\s*{name}\s*=\s*{value}\b"))
+                .unwrap()
+                .is_match(&config)
+        };
+
+        if setting("skip-install", "true") {
+            None
+        } else if setting("dev-mode", "false") {
+            Some(format!("{VENV_LOCATION}/bin/pip install --no-deps ."))
+        } else {
+            Some(format!("{VENV_LOCATION}/bin/pip install --no-deps -e ."))
+        }
+    }
+
+    fn build(&self, app: &App, env: &Environment) -> Result<Option<Phase>> {
+        // collectstatic fails unless STATIC_ROOT is set
+        let static_root_re = Regex::new(r"(?m)^STATIC_ROOT\s*=")?;
+        if PythonProvider::is_django(app, env)? && app.find_match(&static_root_re, "/**/*.py")? {
+            return Ok(Some(Phase::build(Some(
+                "python manage.py collectstatic --noinput".to_string(),
+            ))));
+        }
+
+        if PythonProvider::is_mkdocs(app)? {
+            return Ok(Some(Phase::build(Some("mkdocs build".to_string()))));
+        }
+
+        Ok(None)
+    }
+
+    /// Precompiles the bytecode of the active environment and the app, so it isn't compiled on
+    /// every cold start. Removing the `tests` directories of installed packages is a separate
+    /// opt-in, as some packages import from them.
+    fn optimize(app: &App, env: &Environment, has_build: bool) -> Phase {
+        let env_location = if PackageManager::from_env(env).resolve(app)
+            == Action::InstallWith(PackageManagerType::Conda)
+        {
+            CONDA_ENV_LOCATION
+        } else {
+            VENV_LOCATION
+        };
+
+        let mut optimize = Phase::new("optimize");
+        optimize.depends_on_phase("install");
+        if has_build {
+            optimize.depends_on_phase("build");
+        }
+        if env.is_config_variable_truthy(PYTHON_STRIP_TESTS_ENV_VAR) {
+            optimize.add_cmd(format!(
+                "find {env_location}/lib -path '*/site-packages/*' -type d -name tests -prune -exec rm -rf {{}} +"
+            ));
+        }
+        if env.is_config_variable_truthy(PYTHON_OPTIMIZE_ENV_VAR) {
+            optimize.add_cmd(format!("python -m compileall -q {env_location}/lib ."));
+        }
+
+        optimize
+    }
+
     fn start(&self, app: &App, env: &Environment) -> Result<Option<StartPhase>> {
         if PythonProvider::is_django(app, env)? {
             let app_name = PythonProvider::get_django_app_name(app, env)?;
 
-            return Ok(Some(StartPhase::new(format!(
-                "python manage.py migrate && gunicorn {app_name}"
-            ))));
+            // Migrations can be disabled to run them as a release command instead
+            let migrate = env
+                .get_config_variable("DJANGO_MIGRATE")
+                .map_or(true, |s| s.to_lowercase() != "false" && s != "0");
+            return Ok(Some(StartPhase::new(if migrate {
+                format!("python manage.py migrate && gunicorn {app_name}")
+            } else {
+                format!("gunicorn {app_name}")
+            })));
+        }
+
+        // The generated site is static, so it is served from its output directory with Caddy
+        if PythonProvider::is_mkdocs(app)? {
+            return Ok(Some(StartPhase::new(SpaProvider::caddy_start_command(app))));
+        }
+
+        if let Some(web_start_cmd) = PythonProvider::get_web_app_start_cmd(app)? {
+            return Ok(Some(StartPhase::new(web_start_cmd)));
         }
 
         // the python package is extracted from pyproject.toml, but this can often not be the desired entrypoint
@@ -411,6 +707,61 @@ impl PythonProvider {
         Ok(None)
     }
 
+    /// The entry modules checked for an `app` object, as (file, module, source directory)
+    fn find_web_app_modules(app: &App) -> Result<Vec<(String, String, Option<String>)>> {
+        let mut modules: Vec<(String, String, Option<String>)> = ["main", "app", "app/main"]
+            .iter()
+            .map(|module| (format!("{module}.py"), module.replace('/', "."), None))
+            .collect();
+
+        // src layout, e.g. src/api/main.py is the api.main module with src as the app directory
+        for pattern in ["src/*/main.py", "src/*/app.py"] {
+            for path in app.find_files(pattern)? {
+                let file = app.strip_source_path(&path)?.to_slash_lossy().to_string();
+                let module = file
+                    .trim_start_matches("src/")
+                    .trim_end_matches(".py")
+                    .replace('/', ".");
+                modules.push((file, module, Some("src".to_string())));
+            }
+        }
+
+        Ok(modules)
+    }
+
+    /// Serves an `app` object created in a common entry module with the matching server, if it is installed
+    fn get_web_app_start_cmd(app: &App) -> Result<Option<String>> {
+        let asgi_re = Regex::new(r"(?m)^app(\s*:\s*\w+)?\s*=\s*(FastAPI|Starlette)\(")?;
+        let wsgi_re = Regex::new(r"(?m)^app(\s*:\s*\w+)?\s*=\s*Flask\(")?;
+        let has_uvicorn = PythonProvider::uses_dep(app, "uvicorn")?
+            || PythonProvider::uses_dep(app, "fastapi[standard]")?;
+        let has_gunicorn = PythonProvider::uses_dep(app, "gunicorn")?;
+
+        for (file, module, app_dir) in PythonProvider::find_web_app_modules(app)? {
+            let Some(source) = app.read_file(&file).ok() else {
+                continue;
+            };
+
+            if has_uvicorn && asgi_re.is_match(&source) {
+                let app_dir = app_dir
+                    .map(|dir| format!(" --app-dir {dir}"))
+                    .unwrap_or_default();
+                return Ok(Some(format!(
+                    "uvicorn {module}:app{app_dir} --host 0.0.0.0 --port ${{PORT:-8000}}"
+                )));
+            }
+            // gunicorn binds to $PORT when it is set
+            if has_gunicorn && wsgi_re.is_match(&source) {
+                let chdir = app_dir
+                    .map(|dir| format!(" --chdir {dir}"))
+                    .unwrap_or_default();
+                return Ok(Some(format!("gunicorn{chdir} {module}:app")));
+            }
+        }
+
+        Ok(None)
+    }
+
     fn is_django(app: &App, _env: &Environment) -> Result<bool> {
         let has_manage = app.includes_file("manage.py");
         let imports_django = PythonProvider::uses_dep(app, "django")?;
@@ -454,6 +805,20 @@ impl PythonProvider {
                 }
             }
         }
+
+        // Otherwise use the wsgi module next to the settings module that manage.py loads
+        let settings_re =
+            Regex::new(r#"DJANGO_SETTINGS_MODULE["']\s*,\s*["']([\w.]+)\.settings["']"#)?;
+        if let Some(project) = app
+            .read_file("manage.py")
+            .ok()
+            .and_then(|manage| Some(settings_re.captures(&manage)?[1].to_string()))
+        {
+            if app.includes_file(&format!("{}/wsgi.py", project.replace('.', "/"))) {
+                return Ok(format!("{project}.wsgi"));
+            }
+        }
+
         bail!("Failed to find your WSGI_APPLICATION django setting. Add this to continue.")
     }
 
@@ -484,6 +849,123 @@ impl PythonProvider {
         }))
     }
 
+    /// Installs the selected groups and extras, or everything but the dev dependencies
+    fn get_poetry_install_cmd(env: &Environment) -> String {
+        let list = |name: &str| {
+            env.get_config_variable(name).map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            })
+        };
+        let groups = list("POETRY_GROUPS").filter(|groups| !groups.is_empty());
+        let extras = list("POETRY_EXTRAS").filter(|extras| !extras.is_empty());
+
+        let mut selection = match &groups {
+            Some(groups) => vec![format!("--only {}", groups.join(","))],
+            None => vec!["--without dev".to_string()],
+        };
+        if let Some(extras) = &extras {
+            selection.push(format!("--extras \"{}\"", extras.join(" ")));
+        }
+
+        format!(
+            "poetry install {} --no-interaction --no-ansi",
+            selection.join(" ")
+        )
+    }
+
+    /// Picks the available version closest to the default that satisfies `requires-python`, or
+    /// `None` if the default already does
+    fn parse_requires_python_version(requires_python: &str) -> Option<String> {
+        let specifier_re = Regex::new(r"^(>=|<=|==|!=|~=|>|<)\s*(\d+)(?:\.(\d+|\*))?").ok()?;
+        let specifiers: Vec<(String, (u32, u32), bool)> = requires_python
+            .split(',')
+            .filter_map(|specifier| {
+                let captures = specifier_re.captures(specifier.trim())?;
+                let major = captures.get(2)?.as_str().parse().ok()?;
+                let minor = captures
+                    .get(3)
+                    .and_then(|minor| minor.as_str().parse().ok())
+                    .unwrap_or(0);
+                let is_wildcard = captures.get(3).is_some_and(|minor| minor.as_str() == "*")
+                    || specifier.trim().ends_with(".*");
+                Some((captures[1].to_string(), (major, minor), is_wildcard))
+            })
+            .collect();
+        if specifiers.is_empty() {
+            return None;
+        }
+
+        // Patch versions are ignored, as only a major.minor version can be selected
+        let satisfies = |minor: u32| {
+            let version = (3, minor);
+            specifiers
+                .iter()
+                .all(|(op, bound, is_wildcard)| match op.as_str() {
+                    ">=" | ">" => version >= *bound,
+                    "<=" => version <= *bound,
+                    "<" => version < *bound,
+                    "==" => version == *bound,
+                    "!=" => !(*is_wildcard && version == *bound),
+                    "~=" => version >= *bound && version.0 == bound.0,
+                    _ => true,
+                })
+        };
+
+        if satisfies(DEFAULT_PYTHON_MINOR_VERSION) {
+            return None;
+        }
+
+        AVAILABLE_PYTHON_MINOR_VERSIONS
+            .iter()
+            .copied()
+            .filter(|minor| satisfies(*minor))
+            .min_by_key(|minor| {
+                (
+                    minor.abs_diff(DEFAULT_PYTHON_MINOR_VERSION),
+                    u32::MAX - minor,
+                )
+            })
+            .map(|minor| format!("3.{minor}"))
+    }
+
+    fn is_mkdocs(app: &App) -> Result<bool> {
+        Ok(app.includes_file("mkdocs.yml") && PythonProvider::uses_dep(app, "mkdocs")?)
+    }
+
+    fn get_mkdocs_site_dir(app: &App) -> String {
+        let site_dir_re = Regex::new(r#"(?m)^site_dir:\s*['"]?([^'"\s]+)"#).unwrap();
+        app.read_file("mkdocs.yml")
+            .ok()
+            .and_then(|config| Some(site_dir_re.captures(&config)?[1].to_string()))
+            .unwrap_or_else(|| "site".to_string())
+    }
+
+    /// Runs the command with a shell in the environment, so `&&` and quotes keep working
+    fn get_conda_start_cmd(cmd: &str) -> String {
+        format!(
+            "micromamba run -p {CONDA_ENV_LOCATION} sh -c '{}'",
+            cmd.replace('\'', r"'\''")
+        )
+    }
+
+    fn get_conda_environment_file(app: &App) -> Option<&'static str> {
+        ["environment.yml", "environment.yaml"]
+            .into_iter()
+            .find(|file| app.includes_file(file))
+    }
+
+    fn is_hatch(app: &App) -> bool {
+        app.includes_file("hatch.toml")
+            || app
+                .read_file("pyproject.toml")
+                .is_ok_and(|pyproject| pyproject.contains("[tool.hatch.envs"))
+    }
+
     fn parse_tool_versions_poetry_version(file_content: &str) -> Result<Option<String>> {
         let asdf_versions = parse_tool_versions_content(file_content);
         Ok(asdf_versions.get("poetry").cloned())
@@ -538,6 +1020,14 @@ impl PythonProvider {
         } else if app.includes_file(".tool-versions") {
             let file_content = &app.read_file(".tool-versions")?;
             custom_version = PythonProvider::parse_tool_versions_python_version(file_content)?;
+        } else if custom_version.is_none() {
+            custom_version = PythonProvider::read_pyproject(app)
+                .ok()
+                .flatten()
+                .and_then(|pyproject| pyproject.project?.requires_python)
+                .and_then(|requires_python| {
+                    PythonProvider::parse_requires_python_version(&requires_python)
+                });
         }
 
         // If it's still none, return default
@@ -635,7 +1125,19 @@ impl PythonProvider {
             )
         );
 
-        let entry_point = module_name.clone().map(EntryPoint::Module);
+        // Console scripts from [project.scripts] are installed into the venv's bin directory
+        let script = project.project.as_ref().and_then(|proj| {
+            let scripts = proj.scripts.as_ref()?;
+            match &project_name {
+                Some(name) if scripts.contains_key(name) => Some(name.clone()),
+                _ if scripts.len() == 1 => scripts.keys().next().cloned(),
+                _ => None,
+            }
+        });
+
+        let entry_point = script
+            .map(EntryPoint::Command)
+            .or_else(|| module_name.clone().map(EntryPoint::Module));
 
         ProjectMeta {
             project_name,
@@ -700,6 +1202,267 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_fastapi_start() -> Result<()> {
+        let app = App::new("./examples/python-fastapi")?;
+        assert_eq!(
+            PythonProvider::get_web_app_start_cmd(&app)?,
+            Some("uvicorn main:app --host 0.0.0.0 --port ${PORT:-8000}".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fastapi_src_layout_start() -> Result<()> {
+        let app = App::new("./examples/python-fastapi-src")?;
+        assert_eq!(
+            PythonProvider::get_web_app_start_cmd(&app)?,
+            Some(
+                "uvicorn api.main:app --app-dir src --host 0.0.0.0 --port ${PORT:-8000}"
+                    .to_string()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_scripts_entry_point() -> Result<()> {
+        let project: PyProject = toml::from_str(
+            "[project]\nname = \"api\"\n\n[project.scripts]\napi = \"api.server:run\"\nmigrate = \"api.db:migrate\"\n",
+        )?;
+        let meta = PythonProvider::parse_project(&project);
+        assert!(matches!(meta.entry_point, Some(EntryPoint::Command(cmd)) if cmd == "api"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_poetry_groups_and_extras() {
+        assert_eq!(
+            PythonProvider::get_poetry_install_cmd(&Environment::default()),
+            "poetry install --without dev --no-interaction --no-ansi"
+        );
+
+        let env = Environment::new(BTreeMap::from([
+            (
+                "NIXPACKS_POETRY_GROUPS".to_string(),
+                "main, server".to_string(),
+            ),
+            (
+                "NIXPACKS_POETRY_EXTRAS".to_string(),
+                "postgres,redis".to_string(),
+            ),
+        ]));
+        assert_eq!(
+            PythonProvider::get_poetry_install_cmd(&env),
+            "poetry install --only main,server --extras \"postgres redis\" --no-interaction --no-ansi"
+        );
+    }
+
+    #[test]
+    fn test_django_collectstatic_and_start() -> Result<()> {
+        let app = App::new("./examples/python-django-static")?;
+        let plan = PythonProvider {}
+            .get_build_plan(&app, &Environment::default())?
+            .unwrap();
+        assert_eq!(
+            plan.get_phase("build").and_then(|build| build.cmds.clone()),
+            Some(vec!["python manage.py collectstatic --noinput".to_string()])
+        );
+        assert_eq!(
+            plan.start_phase.and_then(|start| start.cmd),
+            Some("python manage.py migrate && gunicorn mysite.wsgi".to_string())
+        );
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_DJANGO_MIGRATE".to_string(),
+            "false".to_string(),
+        )]));
+        let start = PythonProvider {}.start(&app, &env)?.unwrap();
+        assert_eq!(start.cmd, Some("gunicorn mysite.wsgi".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_optimize_phase() -> Result<()> {
+        let app = App::new("./examples/python-fastapi")?;
+        let plan = PythonProvider {}
+            .get_build_plan(&app, &Environment::default())?
+            .unwrap();
+        assert!(plan.get_phase("optimize").is_none());
+
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_PYTHON_OPTIMIZE".to_string(),
+            "1".to_string(),
+        )]));
+        let plan = PythonProvider {}.get_build_plan(&app, &env)?.unwrap();
+        assert_eq!(
+            plan.get_phase("install")
+                .and_then(|install| install.only_include_files.clone()),
+            Some(vec!["requirements.txt".to_string()])
+        );
+        let optimize = plan.get_phase("optimize").unwrap();
+        assert_eq!(optimize.depends_on, Some(vec!["install".to_string()]));
+        assert_eq!(
+            optimize.cmds,
+            Some(vec!["python -m compileall -q /opt/venv/lib .".to_string()])
+        );
+
+        let env = Environment::new(BTreeMap::from([
+            ("NIXPACKS_PYTHON_OPTIMIZE".to_string(), "1".to_string()),
+            ("NIXPACKS_PYTHON_STRIP_TESTS".to_string(), "1".to_string()),
+        ]));
+        let plan = PythonProvider {}.get_build_plan(&app, &env)?.unwrap();
+        assert_eq!(
+            plan.get_phase("optimize").unwrap().cmds,
+            Some(vec![
+                "find /opt/venv/lib -path '*/site-packages/*' -type d -name tests -prune -exec rm -rf {} +".to_string(),
+                "python -m compileall -q /opt/venv/lib .".to_string()
+            ])
+        );
+
+        let app = App::new("./examples/python-conda")?;
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_PYTHON_OPTIMIZE".to_string(),
+            "1".to_string(),
+        )]));
+        let plan = PythonProvider {}.get_build_plan(&app, &env)?.unwrap();
+        assert_eq!(
+            plan.get_phase("optimize").unwrap().cmds,
+            Some(vec!["python -m compileall -q /opt/conda/lib .".to_string()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dependency_layer() -> Result<()> {
+        let env = Environment::new(BTreeMap::from([(
+            "NIXPACKS_PYTHON_OPTIMIZE".to_string(),
+            "1".to_string(),
+        )]));
+
+        let app = App::new("./examples/python-uv")?;
+        assert!(PythonProvider {}
+            .get_build_plan(&app, &Environment::default())?
+            .unwrap()
+            .get_phase("dependencies")
+            .is_none());
+        let plan = PythonProvider {}.get_build_plan(&app, &env)?.unwrap();
+        let dependencies = plan.get_phase("dependencies").unwrap();
+        assert_eq!(
+            dependencies.cmds,
+            Some(vec!["python -m venv --copies /opt/venv && . /opt/venv/bin/activate && pip install uv==$NIXPACKS_UV_VERSION && uv sync --no-dev --frozen --no-install-project".to_string()])
+        );
+        assert_eq!(
+            dependencies.only_include_files,
+            Some(vec!["pyproject.toml".to_string(), "uv.lock".to_string()])
+        );
+        assert_eq!(
+            plan.get_phase("install").unwrap().depends_on,
+            Some(vec!["setup".to_string(), "dependencies".to_string()])
+        );
+
+        let app = App::new("./examples/python-hatch")?;
+        let plan = PythonProvider {}.get_build_plan(&app, &env)?.unwrap();
+        assert_eq!(
+            plan.get_phase("dependencies").unwrap().only_include_files,
+            Some(vec!["pyproject.toml".to_string()])
+        );
+        assert_eq!(
+            plan.get_phase("install").unwrap().cmds,
+            Some(vec!["/opt/venv/bin/pip install --no-deps .".to_string()])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_conda() -> Result<()> {
+        let app = App::new("./examples/python-conda")?;
+        let plan = PythonProvider {}
+            .get_build_plan(&app, &Environment::default())?
+            .unwrap();
+        assert_eq!(
+            plan.get_phase("install").and_then(|install| install.cmds.clone()),
+            Some(vec!["CONDA_PKGS_DIRS=/root/.cache/conda/pkgs micromamba create --yes --always-copy --prefix /opt/conda --file environment.yml".to_string()])
+        );
+        assert_eq!(
+            plan.start_phase.and_then(|start| start.cmd),
+            Some("micromamba run -p /opt/conda sh -c 'python main.py'".to_string())
+        );
+        assert_eq!(
+            PythonProvider::get_conda_start_cmd("python -c 'print(1)'"),
+            r"micromamba run -p /opt/conda sh -c 'python -c '\''print(1)'\'''"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mkdocs() -> Result<()> {
+        let app = App::new("./examples/python-mkdocs")?;
+        let plan = PythonProvider {}
+            .get_build_plan(&app, &Environment::default())?
+            .unwrap();
+        assert_eq!(
+            plan.get_phase("build").and_then(|build| build.cmds.clone()),
+            Some(vec!["mkdocs build".to_string()])
+        );
+        assert!(plan.get_phase("caddy").is_some());
+        assert_eq!(
+            plan.variables
+                .as_ref()
+                .and_then(|variables| variables.get("NIXPACKS_SPA_OUTPUT_DIR").cloned()),
+            Some("site".to_string())
+        );
+        assert_eq!(
+            plan.start_phase.and_then(|start| start.cmd),
+            Some("exec caddy run --config /assets/Caddyfile --adapter caddyfile 2>&1".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hatch() -> Result<()> {
+        let app = App::new("./examples/python-hatch")?;
+        assert_eq!(
+            PackageManager::Auto.resolve(&app),
+            Action::InstallWith(PackageManagerType::Hatch)
+        );
+        assert_eq!(
+            PythonProvider::get_nix_python_package(&app, &Environment::default())?,
+            (Pkg::new("python312"), PYTHON_NIXPKGS_ARCHIVE.into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_requires_python_version() {
+        assert_eq!(PythonProvider::parse_requires_python_version(">=3.8"), None);
+        assert_eq!(
+            PythonProvider::parse_requires_python_version(">=3.12"),
+            Some("3.12".to_string())
+        );
+        assert_eq!(
+            PythonProvider::parse_requires_python_version(">=3.8, <3.11"),
+            Some("3.10".to_string())
+        );
+        assert_eq!(
+            PythonProvider::parse_requires_python_version("~=3.13.0"),
+            Some("3.13".to_string())
+        );
+        assert_eq!(
+            PythonProvider::parse_requires_python_version("==3.9.*"),
+            Some("3.9".to_string())
+        );
+    }
+
     #[test]
     fn test_pipfile_python_full_version() -> Result<()> {
         let file_content = "\npython_full_version = '3.12.0'\n";
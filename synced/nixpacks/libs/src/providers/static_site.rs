@@ -0,0 +1,227 @@
+// Code relating to sites built with a static site generator that isn't a Node or Python package.
+// Eleventy and MkDocs sites are handled by the Node and Python providers.
+
+use crate::{
+    nixpacks::{
+        app::App,
+        environment::{Environment, EnvironmentVariables},
+        plan::{
+            phase::{Phase, StartPhase},
+            BuildPlan,
+        },
+    },
+    Pkg,
+};
+use anyhow::Result;
+use regex::Regex;
+
+use super::{node::spa::SpaProvider, Provider, ProviderMetadata};
+
+const HUGO_CONFIG_FILES: &[&str] = &["hugo.toml", "hugo.yaml", "hugo.json", "config.toml"];
+const BUNDLE_PATH: &str = "/usr/local/bundle";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Generator {
+    Hugo,
+    Jekyll,
+}
+
+pub struct StaticSiteProvider {}
+
+impl Provider for StaticSiteProvider {
+    fn name(&self) -> &'static str {
+        "static-site"
+    }
+
+    fn detect(&self, app: &App, _env: &Environment) -> Result<bool> {
+        Ok(StaticSiteProvider::get_generator(app).is_some())
+    }
+
+    fn metadata(&self, app: &App, _env: &Environment) -> Result<ProviderMetadata> {
+        let generator = StaticSiteProvider::get_generator(app);
+
+        Ok(ProviderMetadata::from(vec![
+            (generator == Some(Generator::Hugo), "hugo"),
+            (generator == Some(Generator::Jekyll), "jekyll"),
+        ]))
+    }
+
+    fn get_build_plan(&self, app: &App, _env: &Environment) -> Result<Option<BuildPlan>> {
+        let Some(generator) = StaticSiteProvider::get_generator(app) else {
+            return Ok(None);
+        };
+
+        let mut setup = Phase::setup(None);
+        let mut install = Phase::install(None);
+        let (build_cmd, output_dir) = match generator {
+            Generator::Hugo => {
+                setup.add_nix_pkgs(&[Pkg::new("hugo")]);
+                // Hugo Modules are fetched with Go and Git
+                if app.includes_file("go.mod") {
+                    setup.add_nix_pkgs(&[Pkg::new("go"), Pkg::new("git")]);
+                }
+
+                (
+                    "hugo --minify".to_string(),
+                    StaticSiteProvider::get_hugo_output_dir(app),
+                )
+            }
+            Generator::Jekyll => {
+                // Gems with native extensions are built while installing
+                setup.add_nix_pkgs(&[Pkg::new("ruby"), Pkg::new("gcc"), Pkg::new("gnumake")]);
+
+                install.add_cmd("bundle install".to_string());
+                install.add_file_dependency("Gemfile".to_string());
+                if app.includes_file("Gemfile.lock") {
+                    install.add_file_dependency("Gemfile.lock".to_string());
+                }
+
+                (
+                    "JEKYLL_ENV=production bundle exec jekyll build".to_string(),
+                    StaticSiteProvider::get_jekyll_output_dir(app),
+                )
+            }
+        };
+
+        let build = Phase::build(Some(build_cmd));
+        let mut plan = BuildPlan::new(
+            &[setup, install, build, SpaProvider::new_caddy_phase(app)],
+            Some(StartPhase::new(SpaProvider::caddy_start_command(app))),
+        );
+        plan.add_static_assets(SpaProvider::static_assets());
+        plan.add_variables(EnvironmentVariables::from([(
+            "NIXPACKS_SPA_OUTPUT_DIR".to_string(),
+            output_dir,
+        )]));
+        if generator == Generator::Jekyll {
+            // The Nix store is read-only, so gems are installed outside of Ruby's own gem directory
+            plan.add_variables(EnvironmentVariables::from([(
+                "BUNDLE_PATH".to_string(),
+                BUNDLE_PATH.to_string(),
+            )]));
+        }
+
+        Ok(Some(plan))
+    }
+}
+
+impl StaticSiteProvider {
+    fn get_generator(app: &App) -> Option<Generator> {
+        if StaticSiteProvider::get_hugo_config_file(app).is_some() {
+            Some(Generator::Hugo)
+        } else if StaticSiteProvider::is_jekyll(app) {
+            Some(Generator::Jekyll)
+        } else {
+            None
+        }
+    }
+
+    /// `config.toml` is only used by older Hugo sites, so it also needs a Hugo content or layout
+    /// directory
+    fn get_hugo_config_file(app: &App) -> Option<&'static str> {
+        let has_site_dirs = app.includes_directory("content")
+            || app.includes_directory("layouts")
+            || app.includes_directory("themes");
+
+        HUGO_CONFIG_FILES
+            .iter()
+            .copied()
+            .find(|file| app.includes_file(file) && (*file != "config.toml" || has_site_dirs))
+    }
+
+    fn is_jekyll(app: &App) -> bool {
+        app.includes_file("_config.yml")
+            && app
+                .read_file("Gemfile")
+                .is_ok_and(|gemfile| gemfile.contains("jekyll") || gemfile.contains("github-pages"))
+    }
+
+    /// Reads `publishDir` from the config, which defaults to `public`
+    fn get_hugo_output_dir(app: &App) -> String {
+        let publish_dir_re =
+            Regex::new(r#"(?m)^\s*"?publishDir"?\s*[=:]\s*['"]?([^'",\s]+)"#).unwrap();
+        StaticSiteProvider::get_hugo_config_file(app)
+            .and_then(|file| app.read_file(file).ok())
+            .and_then(|config| Some(publish_dir_re.captures(&config)?[1].to_string()))
+            .unwrap_or_else(|| "public".to_string())
+    }
+
+    /// Reads `destination` from `_config.yml`, which defaults to `_site`
+    fn get_jekyll_output_dir(app: &App) -> String {
+        let destination_re = Regex::new(r#"(?m)^destination:\s*['"]?([^'"\s#]+)"#).unwrap();
+        app.read_file("_config.yml")
+            .ok()
+            .and_then(|config| Some(destination_re.captures(&config)?[1].to_string()))
+            .unwrap_or_else(|| "_site".to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hugo() -> Result<()> {
+        let app = App::new("./examples/static-hugo")?;
+        let env = Environment::default();
+        assert!(StaticSiteProvider {}.detect(&app, &env)?);
+
+        let plan = StaticSiteProvider {}.get_build_plan(&app, &env)?.unwrap();
+        assert_eq!(
+            plan.get_phase("build").and_then(|build| build.cmds.clone()),
+            Some(vec!["hugo --minify".to_string()])
+        );
+        assert!(plan.get_phase("caddy").is_some());
+        assert_eq!(
+            plan.variables
+                .and_then(|variables| variables.get("NIXPACKS_SPA_OUTPUT_DIR").cloned()),
+            Some("public".to_string())
+        );
+        assert_eq!(
+            plan.start_phase.and_then(|start| start.cmd),
+            Some("exec caddy run --config /assets/Caddyfile --adapter caddyfile 2>&1".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_jekyll() -> Result<()> {
+        let app = App::new("./examples/static-jekyll")?;
+        let env = Environment::default();
+        assert!(StaticSiteProvider {}.detect(&app, &env)?);
+
+        let plan = StaticSiteProvider {}.get_build_plan(&app, &env)?.unwrap();
+        let install = plan.get_phase("install").unwrap();
+        assert_eq!(install.cmds, Some(vec!["bundle install".to_string()]));
+        assert_eq!(
+            install.only_include_files,
+            Some(vec!["Gemfile".to_string(), "Gemfile.lock".to_string()])
+        );
+        assert_eq!(
+            plan.get_phase("build").and_then(|build| build.cmds.clone()),
+            Some(vec![
+                "JEKYLL_ENV=production bundle exec jekyll build".to_string()
+            ])
+        );
+        let variables = plan.variables.unwrap();
+        assert_eq!(
+            variables.get("NIXPACKS_SPA_OUTPUT_DIR"),
+            Some(&"_site".to_string())
+        );
+        assert_eq!(
+            variables.get("BUNDLE_PATH"),
+            Some(&"/usr/local/bundle".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_not_static_site() -> Result<()> {
+        let app = App::new("./examples/python-uv")?;
+        assert!(!StaticSiteProvider {}.detect(&app, &Environment::default())?);
+
+        Ok(())
+    }
+}